@@ -0,0 +1,295 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::mem;
+use crate::flood_fill::Connectivity;
+use crate::geometry::Vec2;
+use crate::grid::GridMap;
+
+/// Computes the next value of the cell at the coordinate from the previous state.
+pub type Rule<A> = Box<dyn Fn(&GridMap<A>, Vec2) -> A>;
+
+/// Chain reactions within a step: once every cell has been updated, cells that [fire](Cascade::fires)
+/// affect their neighbours, which can make those fire as well. Every cell fires at most once per step.
+///
+/// The cells that fire right after the update are the first wave, the ones they make fire the
+/// second, and so on. The propagation works through a queue, so it does not recurse however large
+/// the grid.
+pub struct Cascade<A> {
+    /// Whether a cell fires, checked after the update and whenever a neighbour fired.
+    pub fires: Box<dyn Fn(&A) -> bool>,
+    /// Applied to every neighbour of a firing cell.
+    pub spread: Box<dyn Fn(&mut A)>,
+    /// Applied to every cell that fired once nothing fires any more.
+    pub settle: Box<dyn Fn(&mut A)>
+}
+
+/// What happened in one step.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct StepStats {
+    /// Number of the step, the first one being 1.
+    pub step: usize,
+    /// Cells that differ from the previous step.
+    pub changed: usize,
+    /// Cells that fired in the [Cascade].
+    pub fired: usize
+}
+
+/// A repeating sequence of states: the state after `start + period` steps is the state after `start`
+/// steps.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize
+}
+
+/// A cellular automaton over a [GridMap]. Every step computes each cell from the previous state with
+/// the rule, so cells never see the updates of their neighbours, then runs the [Cascade] if there is
+/// one.
+pub struct Automaton<A> {
+    grid: GridMap<A>,
+    /// The previous state, reused for the next one.
+    buffer: GridMap<A>,
+    steps: usize,
+    connectivity: Connectivity,
+    rule: Rule<A>,
+    cascade: Option<Cascade<A>>,
+    /// Whether each cell fired in the current step.
+    fired: GridMap<bool>,
+    /// Cells that fired in the last step, in firing order.
+    fired_order: Vec<Vec2>,
+    /// Where each wave of the last step starts in `fired_order`.
+    wave_starts: Vec<usize>
+}
+impl<A : Clone + PartialEq> Automaton<A> {
+    /// `rule` gives the next value of the cell at the coordinate, `connectivity` decides which cells a
+    /// cascade spreads to.
+    pub fn new(
+        grid: GridMap<A>, connectivity: Connectivity, rule: impl Fn(&GridMap<A>, Vec2) -> A + 'static
+    ) -> Self {
+        Automaton {
+            buffer: grid.clone(), fired: GridMap::filled(grid.height(), grid.width(), false), grid, steps: 0,
+            connectivity, rule: Box::new(rule), cascade: None, fired_order: Vec::new(), wave_starts: Vec::new()
+        }
+    }
+
+    pub fn with_cascade(
+        self, fires: impl Fn(&A) -> bool + 'static, spread: impl Fn(&mut A) + 'static,
+        settle: impl Fn(&mut A) + 'static
+    ) -> Self {
+        let cascade = Cascade { fires: Box::new(fires), spread: Box::new(spread), settle: Box::new(settle) };
+        Automaton { cascade: Some(cascade), ..self }
+    }
+
+    pub fn grid(&self) -> &GridMap<A> { &self.grid }
+
+    /// Cells that fired in the last step, in the order they fired.
+    pub fn fired(&self) -> &[Vec2] { &self.fired_order }
+
+    /// Cells that fired in the last step, wave by wave.
+    pub fn waves(&self) -> impl Iterator<Item = &[Vec2]> {
+        let ends = self.wave_starts.iter().skip(1).copied().chain([self.fired_order.len()]);
+        self.wave_starts.iter().zip(ends).map(|(start, end)| &self.fired_order[*start..end])
+    }
+
+    /// Number of steps taken, including the ones skipped over by [Automaton::advance_to].
+    pub fn steps(&self) -> usize { self.steps }
+
+    pub fn step(&mut self) -> StepStats {
+        for (c, cell) in self.grid.each_coord().zip(self.buffer.values_mut()) {
+            *cell = (self.rule)(&self.grid, c);
+        }
+        mem::swap(&mut self.grid, &mut self.buffer);
+        let fired = self.cascade();
+        self.steps += 1;
+
+        let changed = self.grid.values().iter().zip(self.buffer.values()).filter(|(a, b)| a != b).count();
+        StepStats { step: self.steps, changed, fired }
+    }
+
+    /// Runs the cascade on the freshly updated grid, returning how many cells fired.
+    fn cascade(&mut self) -> usize {
+        self.fired_order.clear();
+        self.wave_starts.clear();
+        let Some(cascade) = &self.cascade else { return 0 };
+
+        self.fired.values_mut().fill(false);
+        for p in self.grid.each_point() {
+            if (cascade.fires)(p.value) {
+                *self.fired.get_mut(&p.coords).unwrap() = true;
+                self.fired_order.push(p.coords);
+            }
+        }
+
+        // `fired_order` doubles as the queue: everything before `next` has spread already.
+        let mut next = 0;
+        while next < self.fired_order.len() {
+            self.wave_starts.push(next);
+            let wave_end = self.fired_order.len();
+            for idx in next..wave_end {
+                for n in self.fired_order[idx].adjacent(self.connectivity.diagonals()) {
+                    let Some(cell) = self.grid.get_mut(&n) else { continue };
+                    (cascade.spread)(cell);
+                    let fired = self.fired.get_mut(&n).unwrap();
+                    if !*fired && (cascade.fires)(cell) {
+                        *fired = true;
+                        self.fired_order.push(n);
+                    }
+                }
+            }
+            next = wave_end;
+        }
+
+        for (cell, fired) in self.grid.values_mut().iter_mut().zip(self.fired.values()) {
+            if *fired { (cascade.settle)(cell) }
+        }
+        self.fired_order.len()
+    }
+}
+impl<A : Clone + Eq + Hash> Automaton<A> {
+    /// Steps until the state after `target` steps is reached. Once a state repeats, the rest of the
+    /// cycle is skipped over, which makes huge targets cheap. Returns the cycle if there was one.
+    pub fn advance_to(&mut self, target: usize) -> Option<Cycle> {
+        let mut seen = HashMap::from([(self.grid.values().to_vec(), self.steps)]);
+        while self.steps < target {
+            self.step();
+            match seen.entry(self.grid.values().to_vec()) {
+                Entry::Occupied(first) => {
+                    let cycle = Cycle { start: *first.get(), period: self.steps - first.get() };
+                    for _ in 0..(target - self.steps) % cycle.period { self.step(); }
+                    self.steps = target;
+                    return Some(cycle)
+                }
+                Entry::Vacant(entry) => { entry.insert(self.steps); }
+            }
+        }
+        None
+    }
+
+    /// Steps until `found` holds for a step and the state after it, returning that step. `None` if a
+    /// state repeats first, as then it never will.
+    pub fn first_step(&mut self, found: impl Fn(&StepStats, &GridMap<A>) -> bool) -> Option<usize> {
+        let mut seen = HashMap::from([(self.grid.values().to_vec(), self.steps)]);
+        loop {
+            let stats = self.step();
+            if found(&stats, &self.grid) { return Some(stats.step) }
+            if seen.insert(self.grid.values().to_vec(), self.steps).is_some() { return None }
+        }
+    }
+
+    /// Steps until a state repeats, which only ever happens if the cells have finitely many values.
+    pub fn find_cycle(&mut self) -> Cycle {
+        let mut seen = HashMap::from([(self.grid.values().to_vec(), self.steps)]);
+        loop {
+            self.step();
+            if let Some(start) = seen.insert(self.grid.values().to_vec(), self.steps) {
+                return Cycle { start, period: self.steps - start }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Conway's Game of Life.
+    fn life(grid: GridMap<bool>) -> Automaton<bool> {
+        Automaton::new(grid, Connectivity::Eight, |grid, c| {
+            let alive = c.adjacent(true).filter(|n| grid.get(n) == Some(&true)).count();
+            alive == 3 || (alive == 2 && *grid.get(&c).unwrap())
+        })
+    }
+
+    /// A single cell counting 0, 1, …, 5 and then going back to 3.
+    fn counter() -> Automaton<u32> {
+        Automaton::new(GridMap::filled(1, 1, 0), Connectivity::Four, |grid, c| {
+            let v = *grid.get(&c).unwrap();
+            if v == 5 { 3 } else { v + 1 }
+        })
+    }
+
+    /// Dumbo octopuses: every step adds 1, octopuses above 9 flash, add 1 to all 8 neighbours and
+    /// go back to 0.
+    fn octopuses(rows: &[&str]) -> Automaton<u32> {
+        let rows = rows.iter().map(|row| row.chars().map(|c| c.to_digit(10).unwrap()).collect()).collect();
+        Automaton::new(GridMap::new_with_data(rows), Connectivity::Eight, |grid, c| grid.get(&c).unwrap() + 1)
+            .with_cascade(|energy| *energy > 9, |energy| *energy += 1, |energy| *energy = 0)
+    }
+
+    fn coords(cells: &[(usize, usize)]) -> Vec<Vec2> { cells.iter().map(|(x, y)| Vec2::new(*x, *y)).collect() }
+
+    #[test]
+    fn cascade_waves_in_firing_order() {
+        let mut automaton = octopuses(&["11111", "19991", "19191", "19991", "11111"]);
+        assert_eq!(automaton.step(), StepStats { step: 1, changed: 25, fired: 9 });
+
+        let ring = coords(&[(1, 1), (1, 2), (1, 3), (2, 1), (2, 3), (3, 1), (3, 2), (3, 3)]);
+        assert_eq!(automaton.waves().collect::<Vec<_>>(), [&ring[..], &[Vec2::new(2, 2)]]);
+        assert_eq!(automaton.fired(), [&ring[..], &[Vec2::new(2, 2)]].concat());
+        let expected = GridMap::new_with_data(vec![
+            vec![3, 4, 5, 4, 3], vec![4, 0, 0, 0, 4], vec![5, 0, 0, 0, 5], vec![4, 0, 0, 0, 4], vec![3, 4, 5, 4, 3]
+        ]);
+        assert_eq!(automaton.grid().values(), expected.values());
+
+        // Nothing reaches 10 in the second step, which also clears the waves of the first one.
+        assert_eq!(automaton.step(), StepStats { step: 2, changed: 25, fired: 0 });
+        assert_eq!(automaton.waves().count(), 0);
+        assert!(automaton.fired().is_empty());
+    }
+
+    #[test]
+    fn cascade_across_a_large_grid() {
+        // A single cell sets off the whole grid one Manhattan distance at a time, so there are almost
+        // 2000 waves. Scanning the million cells for every wave would take billions of checks.
+        let size = 1000;
+        let mut automaton = Automaton::new(
+            GridMap::from_fn(size, size, |c| u8::from(c == Vec2::new(0, 0))), Connectivity::Four,
+            |grid, c| *grid.get(&c).unwrap()
+        ).with_cascade(|v| *v > 0, |v| *v = v.saturating_add(1), |v| *v = 0);
+
+        assert_eq!(automaton.step(), StepStats { step: 1, changed: 1, fired: size * size });
+        assert_eq!(automaton.waves().count(), 2 * size - 1);
+        for (distance, wave) in automaton.waves().enumerate() {
+            assert_eq!(wave.len(), distance.min(2 * size - 2 - distance) + 1, "wave {}", distance);
+            assert!(wave.iter().all(|c| c.manhattan(&Vec2::new(0, 0)) == distance), "wave {}", distance);
+        }
+        assert!(automaton.grid().values().iter().all(|v| *v == 0));
+    }
+
+    #[test]
+    fn blinker_has_period_2() {
+        let blinker = GridMap::from_fn(5, 5, |c| c.x == 2 && (1..=3).contains(&c.y));
+        let mut automaton = life(blinker.clone());
+        assert_eq!(automaton.step(), StepStats { step: 1, changed: 4, fired: 0 });
+        assert_eq!(automaton.find_cycle(), Cycle { start: 1, period: 2 });
+
+        let mut automaton = life(blinker.clone());
+        assert_eq!(automaton.find_cycle(), Cycle { start: 0, period: 2 });
+        assert_eq!(automaton.grid().values(), blinker.values());
+    }
+
+    #[test]
+    fn cycle_after_a_prefix() {
+        assert_eq!(counter().find_cycle(), Cycle { start: 3, period: 3 });
+
+        let mut automaton = counter();
+        assert_eq!(automaton.advance_to(2), None);
+        assert_eq!(automaton.grid().values(), [2]);
+        assert_eq!(automaton.first_step(|_, grid| grid.values() == [7]), None);
+    }
+
+    #[test]
+    fn advance_to_skips_the_cycle() {
+        let target = 1_000_000_000_000;
+        let mut automaton = counter();
+        assert_eq!(automaton.advance_to(target), Some(Cycle { start: 3, period: 3 }));
+        assert_eq!(automaton.steps(), target);
+
+        // The state after `target` steps is the one after the same number of steps into the cycle.
+        let mut direct = counter();
+        for _ in 0..3 + (target - 3) % 3 { direct.step(); }
+        assert_eq!(automaton.grid().values(), direct.grid().values());
+        assert_eq!(automaton.grid().values(), [4]);
+    }
+}
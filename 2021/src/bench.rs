@@ -0,0 +1,113 @@
+use core::fmt::Write;
+use std::time::Duration;
+use serde::Serialize;
+use advent_of_code::parsing::{InputError, InputSource};
+use crate::registry::Day;
+
+/// Minimum and median of the measured durations.
+#[derive(Debug, Copy, Clone)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration
+}
+impl Stats {
+    /// `None` if there are no samples.
+    pub fn of(mut samples: Vec<Duration>) -> Option<Self> {
+        samples.sort();
+        let min = *samples.first()?;
+        let median = samples[(samples.len() - 1) / 2];
+        Some(Stats { min, median })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    pub day: u8,
+    pub part: u8,
+    pub input: InputSource,
+    pub runs: usize,
+    pub parse: Stats,
+    pub solve: Stats
+}
+impl BenchResult {
+    pub fn total(&self) -> Stats {
+        Stats { min: self.parse.min + self.solve.min, median: self.parse.median + self.solve.median }
+    }
+}
+
+/// Parses and solves the part `runs` times, `runs` must be at least 1.
+///
+/// Returns `None` if the day does not have such a part.
+pub fn bench(day: &Day, part: u8, input: &advent_of_code::parsing::Input, source: &InputSource, runs: usize)
+    -> Option<Result<BenchResult, InputError>>
+{
+    let mut parse = Vec::<Duration>::with_capacity(runs);
+    let mut solve = Vec::<Duration>::with_capacity(runs);
+    let _span = day.span(part).entered();
+    for _ in 0..runs.max(1) {
+        match day.solution.time(part, input)? {
+            Ok(timing) => {
+                std::hint::black_box(timing.answer);
+                parse.push(timing.parse);
+                solve.push(timing.solve);
+            }
+            Err(err) => return Some(Err(err))
+        }
+    }
+
+    Some(Ok(BenchResult {
+        day: day.day, part, input: source.clone(), runs: parse.len(),
+        parse: Stats::of(parse)?, solve: Stats::of(solve)?
+    }))
+}
+
+fn format_duration(d: Duration) -> String { format!("{:.2?}", d) }
+
+pub fn render_table(results: &[BenchResult]) -> String {
+    let header = ["day", "part", "input", "runs", "parse min", "parse median", "solve min", "solve median", "total median"];
+    let rows = results.iter().map(|r| [
+        r.day.to_string(), r.part.to_string(), r.input.to_string(), r.runs.to_string(),
+        format_duration(r.parse.min), format_duration(r.parse.median),
+        format_duration(r.solve.min), format_duration(r.solve.median),
+        format_duration(r.total().median)
+    ]).collect::<Vec<_>>();
+
+    let widths = (0..header.len()).map(|col|
+        rows.iter().map(|row| row[col].chars().count()).chain([header[col].len()]).max().unwrap()
+    ).collect::<Vec<_>>();
+
+    let mut s = String::new();
+    let mut write_row = |cells: &[&str]| {
+        for (col, cell) in cells.iter().enumerate() {
+            if col > 0 { s.push_str("  "); }
+            // Text columns are left aligned, numbers right aligned.
+            if col == 2 { write!(s, "{:<w$}", cell, w = widths[col]).unwrap(); }
+            else { write!(s, "{:>w$}", cell, w = widths[col]).unwrap(); }
+        }
+        s.push('\n');
+    };
+    write_row(&header);
+    for row in &rows {
+        write_row(&row.iter().map(|c| c.as_str()).collect::<Vec<_>>());
+    }
+    s
+}
+
+#[derive(Serialize)]
+struct JsonStats { min_ns: u64, median_ns: u64 }
+impl From<Stats> for JsonStats {
+    fn from(s: Stats) -> Self {
+        JsonStats { min_ns: s.min.as_nanos() as u64, median_ns: s.median.as_nanos() as u64 }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonResult { day: u8, part: u8, input: String, runs: usize, parse: JsonStats, solve: JsonStats }
+
+pub fn render_json(results: &[BenchResult]) -> String {
+    let json = results.iter().map(|r| JsonResult {
+        day: r.day, part: r.part, input: r.input.to_string(), runs: r.runs,
+        parse: r.parse.into(), solve: r.solve.into()
+    }).collect::<Vec<_>>();
+    serde_json::to_string_pretty(&json).unwrap()
+}
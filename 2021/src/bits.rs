@@ -0,0 +1,20 @@
+pub fn bits_to_u32(slice: &[u8]) -> u32 {
+    slice.iter().rev().enumerate()
+        .map(|(idx, bit)| (*bit as u32) << idx)
+        .fold(0, |a, b| { a | b })
+}
+pub fn bits_bool_to_u32(slice: &[bool]) -> u32 {
+    slice.iter().rev().enumerate()
+        .map(|(idx, bit)| (if *bit { 1 } else { 0 }) << idx)
+        .fold(0, |a, b| { a | b })
+}
+pub fn bits_to_usize(slice: &[u8]) -> usize {
+    slice.iter().rev().enumerate()
+        .map(|(idx, bit)| (*bit as usize) << idx)
+        .fold(0, |a, b| { a | b })
+}
+pub fn bits_bool_to_usize(slice: &[bool]) -> usize {
+    slice.iter().rev().enumerate()
+        .map(|(idx, bit)| (if *bit { 1 } else { 0 }) << idx)
+        .fold(0, |a, b| { a | b })
+}
@@ -0,0 +1,148 @@
+use core::fmt::{Display, Formatter};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use advent_of_code::parsing::{InputError, InputSource};
+use crate::registry;
+use advent_of_code::solution::Answer;
+
+/// Expected answers for every day, part and input variant.
+pub const MANIFEST_PATH: &str = "answers.toml";
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ExpectedAnswer { Number(i64), Text(String) }
+impl From<ExpectedAnswer> for Answer {
+    fn from(a: ExpectedAnswer) -> Self {
+        match a {
+            ExpectedAnswer::Number(n) => n.into(),
+            ExpectedAnswer::Text(s) => s.into()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExpectedParts {
+    part1: Option<ExpectedAnswer>,
+    part2: Option<ExpectedAnswer>
+}
+
+/// `day<N>` -> input variant -> answers.
+type RawManifest = BTreeMap<String, BTreeMap<String, ExpectedParts>>;
+
+#[derive(Debug)]
+pub enum ManifestError {
+    Io(PathBuf, std::io::Error),
+    Toml(PathBuf, toml::de::Error),
+    InvalidDay(PathBuf, String)
+}
+impl Display for ManifestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ManifestError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            ManifestError::Toml(path, err) => write!(f, "{}: {}", path.display(), err),
+            ManifestError::InvalidDay(path, key) =>
+                write!(f, "{}: expected a table named 'day<N>', found '{}'", path.display(), key)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Expectation {
+    pub day: u8,
+    pub input: InputSource,
+    pub part: u8,
+    pub expected: Answer
+}
+impl Display for Expectation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "day {:2}, part {}, input {}", self.day, self.part, self.input)
+    }
+}
+
+pub fn load(path: &Path) -> Result<Vec<Expectation>, ManifestError> {
+    let text = std::fs::read_to_string(path).map_err(|err| ManifestError::Io(path.to_path_buf(), err))?;
+    let raw: RawManifest = toml::from_str(&text).map_err(|err| ManifestError::Toml(path.to_path_buf(), err))?;
+
+    let mut expectations = Vec::<Expectation>::new();
+    for (day_key, inputs) in raw {
+        let day = day_key.strip_prefix("day").and_then(|n| n.parse::<u8>().ok())
+            .ok_or_else(|| ManifestError::InvalidDay(path.to_path_buf(), day_key.clone()))?;
+        for (variant, parts) in inputs {
+            let input = InputSource::variant(&variant);
+            for (part, expected) in [(1, parts.part1), (2, parts.part2)] {
+                if let Some(expected) = expected {
+                    expectations.push(Expectation { day, input: input.clone(), part, expected: expected.into() });
+                }
+            }
+        }
+    }
+    expectations.sort_by_key(|e| (e.day, e.part, e.input.to_string()));
+    Ok(expectations)
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Passed,
+    Mismatch { actual: Answer },
+    Failed(InputError),
+    UnknownDay,
+    UnknownPart
+}
+
+pub struct CheckResult {
+    pub expectation: Expectation,
+    pub outcome: Outcome
+}
+impl CheckResult {
+    pub fn passed(&self) -> bool { matches!(self.outcome, Outcome::Passed) }
+}
+impl Display for CheckResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: ", self.expectation)?;
+        match &self.outcome {
+            Outcome::Passed => f.write_str("ok"),
+            Outcome::Mismatch { actual } =>
+                write!(f, "MISMATCH\n  expected: {}\n  actual:   {}", self.expectation.expected, actual),
+            Outcome::Failed(err) => write!(f, "FAILED: {}", err),
+            Outcome::UnknownDay => f.write_str("FAILED: day is not registered"),
+            Outcome::UnknownPart => f.write_str("FAILED: day has no such part")
+        }
+    }
+}
+
+/// Runs the registered solution for every expectation and compares the answers.
+pub fn check(expectations: &[Expectation]) -> Vec<CheckResult> {
+    expectations.iter().map(|expectation| {
+        let outcome = match registry::find(expectation.day) {
+            None => Outcome::UnknownDay,
+            Some(day) => {
+                let _span = day.span(expectation.part).entered();
+                let result = expectation.input.load(expectation.day)
+                    .map(|input| day.solution.run(expectation.part, &input));
+                match result {
+                    Err(err) | Ok(Some(Err(err))) => Outcome::Failed(err),
+                    Ok(None) => Outcome::UnknownPart,
+                    Ok(Some(Ok(actual))) =>
+                        if actual == expectation.expected { Outcome::Passed } else { Outcome::Mismatch { actual } }
+                }
+            }
+        };
+        CheckResult { expectation: expectation.clone(), outcome }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use itertools::Itertools;
+    use super::*;
+
+    #[test]
+    fn answers_match_manifest() {
+        let expectations = load(Path::new(MANIFEST_PATH)).unwrap();
+        let failures = check(&expectations).into_iter().filter(|r| !r.passed()).collect_vec();
+        assert!(failures.is_empty(), "{} of {} answers are wrong:\n{}", failures.len(), expectations.len(), failures.iter().join("\n"));
+    }
+}
@@ -0,0 +1,354 @@
+use core::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use itertools::Itertools;
+use crate::bench;
+use crate::check;
+use crate::logging;
+use advent_of_code::parsing::{InputError, InputSource};
+use crate::registry;
+use crate::registry::Day;
+
+const USAGE: &str = "\
+Usage:
+  aoc run <day> [part] [input]   run one day (all of its parts if no part is given)
+  aoc run all [input]            run every registered day and part
+  aoc list                       list registered days, their parts and inputs
+  aoc check [day] [--manifest <path>]
+                                 compare answers against the expected ones (answers.toml)
+  aoc bench <day|all> [part] [input] [--runs <n>] [--json]
+                                 time parsing and solving, 10 runs by default
+
+Input (the real input if not given):
+  --input <variant>              use data/day<N>_<variant>.txt, for example `--input test1`
+  --file <path>                  use the given file, `-` reads stdin
+
+Diagnostics (written to stderr):
+  -v, --verbose                  show debug output of the puzzles, `-vv` shows trace output as well
+  AOC_LOG=<filter>               tracing filter, for example `AOC_LOG=aoc::day16=trace` or
+                                 `AOC_LOG='[day{day=16}]=debug'`, overrides `-v`";
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Selection {
+    All,
+    Day { day: u8, part: Option<u8> }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Command {
+    Run { selection: Selection, source: InputSource },
+    Check { day: Option<u8>, manifest: PathBuf },
+    Bench { selection: Selection, source: InputSource, runs: usize, json: bool },
+    List,
+    Help
+}
+
+/// A parsed command line.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Args {
+    pub command: Command,
+    /// How many times `-v` was given.
+    pub verbosity: u8
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    MissingArgument(&'static str),
+    UnexpectedArgument(String),
+    UnknownCommand(String),
+    InvalidNumber { what: &'static str, value: String },
+    UnknownDay(u8),
+    UnknownPart { day: u8, part: u8 },
+    Input(InputError),
+    Manifest(check::ManifestError),
+    /// Several parts or answers were checked and some of them failed, the errors have been reported
+    /// already.
+    Failed { what: &'static str, failed: usize, total: usize }
+}
+impl From<InputError> for CliError {
+    fn from(err: InputError) -> Self { CliError::Input(err) }
+}
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CliError::MissingArgument(what) => write!(f, "missing argument: {}\n\n{}", what, USAGE),
+            CliError::UnexpectedArgument(arg) => write!(f, "unexpected argument '{}'\n\n{}", arg, USAGE),
+            CliError::UnknownCommand(cmd) => write!(f, "unknown command '{}'\n\n{}", cmd, USAGE),
+            CliError::InvalidNumber { what, value } => write!(f, "invalid {} '{}', expected a number", what, value),
+            CliError::UnknownDay(day) => write!(
+                f, "day {} is not registered, known days: {}",
+                day, registry::DAYS.iter().map(|d| d.day).join(", ")
+            ),
+            CliError::UnknownPart { day, part } => write!(
+                f, "day {} has no part {}, known parts: {}",
+                day, part, registry::find(*day).map(|d| d.part_numbers().join(", ")).unwrap_or_default()
+            ),
+            CliError::Input(err) => write!(f, "{}", err),
+            CliError::Manifest(err) => write!(f, "{}", err),
+            CliError::Failed { what, failed, total } => write!(f, "{} of {} {} failed", failed, total, what)
+        }
+    }
+}
+
+fn parse_number(what: &'static str, value: &str) -> Result<u8, CliError> {
+    value.parse::<u8>().map_err(|_| CliError::InvalidNumber { what, value: String::from(value) })
+}
+
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, CliError> {
+    // Verbosity flags are accepted anywhere, including before the command.
+    let mut verbosity = 0u8;
+    let mut args = args.into_iter().filter(|arg| {
+        let flag_verbosity = match arg.as_str() {
+            "--verbose" => 1,
+            "-v" | "-vv" | "-vvv" => arg.len() - 1,
+            _ => return true
+        };
+        verbosity = verbosity.saturating_add(flag_verbosity as u8);
+        false
+    }).collect::<Vec<_>>().into_iter();
+    let command = match args.next() {
+        None => return Ok(Args { command: Command::Help, verbosity }),
+        Some(command) => command
+    };
+
+    let mut source = InputSource::Real;
+    let mut manifest = PathBuf::from(check::MANIFEST_PATH);
+    let mut runs = 10usize;
+    let mut json = false;
+    let mut positional = Vec::<String>::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => {
+                let name = args.next().ok_or(CliError::MissingArgument("input variant"))?;
+                source = InputSource::variant(&name);
+            }
+            "--file" => {
+                let path = args.next().ok_or(CliError::MissingArgument("input file"))?;
+                source = if path == "-" { InputSource::Stdin } else { InputSource::File(PathBuf::from(path)) };
+            }
+            "--manifest" => {
+                manifest = PathBuf::from(args.next().ok_or(CliError::MissingArgument("manifest path"))?);
+            }
+            "--runs" => {
+                let value = args.next().ok_or(CliError::MissingArgument("number of runs"))?;
+                runs = value.parse::<usize>().ok().filter(|runs| *runs > 0)
+                    .ok_or(CliError::InvalidNumber { what: "number of runs", value })?;
+            }
+            "--json" => json = true,
+            _ => positional.push(arg)
+        }
+    }
+    let mut positional = positional.into_iter();
+
+    let mut selection = || -> Result<Selection, CliError> {
+        let day = positional.next().ok_or(CliError::MissingArgument("day"))?;
+        if day == "all" {
+            Ok(Selection::All)
+        }
+        else {
+            let day = parse_number("day", &day)?;
+            let part = positional.next().map(|part| parse_number("part", &part)).transpose()?;
+            Ok(Selection::Day { day, part })
+        }
+    };
+
+    let command = match command.as_str() {
+        "run" => Command::Run { selection: selection()?, source },
+        "bench" => Command::Bench { selection: selection()?, source, runs, json },
+        "check" => {
+            let day = positional.next().map(|day| parse_number("day", &day)).transpose()?;
+            Command::Check { day, manifest }
+        }
+        "list" => Command::List,
+        "help" | "-h" | "--help" => Command::Help,
+        other => return Err(CliError::UnknownCommand(String::from(other)))
+    };
+
+    match positional.next() {
+        Some(extra) => Err(CliError::UnexpectedArgument(extra)),
+        None => Ok(Args { command, verbosity })
+    }
+}
+
+/// Resolves a selection into the `(day, part)` pairs it refers to, validating them against the
+/// registry.
+pub fn resolve(selection: &Selection) -> Result<Vec<(&'static Day, u8)>, CliError> {
+    match selection {
+        Selection::All => Ok(
+            registry::DAYS.iter().flat_map(|day| day.part_numbers().map(move |part| (day, part))).collect()
+        ),
+        Selection::Day { day, part } => {
+            let found = registry::find(*day).ok_or(CliError::UnknownDay(*day))?;
+            match part {
+                None => Ok(found.part_numbers().map(|part| (found, part)).collect()),
+                Some(part) => {
+                    if !found.has_part(*part) { return Err(CliError::UnknownPart { day: *day, part: *part }) }
+                    Ok(vec![(found, *part)])
+                }
+            }
+        }
+    }
+}
+
+pub fn run(args: impl IntoIterator<Item = String>) -> Result<(), CliError> {
+    let Args { command, verbosity } = parse(args)?;
+    logging::init(verbosity);
+
+    match command {
+        Command::Help => println!("{}", USAGE),
+        Command::List => {
+            for day in registry::DAYS {
+                println!(
+                    "day {:2}: parts {}, inputs: {}",
+                    day.day, day.part_numbers().join(", "), advent_of_code::parsing::variants(day.day).join(", ")
+                );
+            }
+        }
+        Command::Run { selection, source } => {
+            let targets = resolve(&selection)?;
+            // Stdin can only be read once, so every day gets the same input.
+            let stdin = if source == InputSource::Stdin { Some(source.load(0)?) } else { None };
+
+            let mut failed = 0usize;
+            for (day, part) in &targets {
+                let input = match &stdin {
+                    Some(input) => Ok(input.clone()),
+                    None => source.load(day.day)
+                };
+                let _span = day.span(*part).entered();
+                match input.and_then(|input| day.solution.run(*part, &input).unwrap()) {
+                    Ok(answer) => {
+                        let separator = if answer.to_string().contains('\n') { "\n" } else { " " };
+                        println!("day {:2}, part {}:{}{}", day.day, part, separator, answer);
+                    }
+                    Err(err) if targets.len() == 1 => return Err(err.into()),
+                    Err(err) => {
+                        eprintln!("day {:2}, part {}: error: {}", day.day, part, err);
+                        failed += 1;
+                    }
+                }
+            }
+            if failed > 0 { return Err(CliError::Failed { what: "parts", failed, total: targets.len() }) }
+        }
+        Command::Check { day, manifest } => {
+            let expectations = check::load(&manifest).map_err(CliError::Manifest)?
+                .into_iter().filter(|e| day.is_none_or(|day| e.day == day)).collect::<Vec<_>>();
+            let results = check::check(&expectations);
+            for result in &results {
+                println!("{}", result);
+            }
+            let failed = results.iter().filter(|r| !r.passed()).count();
+            if failed > 0 { return Err(CliError::Failed { what: "answers", failed, total: results.len() }) }
+        }
+        Command::Bench { selection, source, runs, json } => {
+            let targets = resolve(&selection)?;
+            let stdin = if source == InputSource::Stdin { Some(source.load(0)?) } else { None };
+
+            let mut results = Vec::<bench::BenchResult>::new();
+            let mut failed = 0usize;
+            for (day, part) in &targets {
+                let input = match &stdin {
+                    Some(input) => Ok(input.clone()),
+                    None => source.load(day.day)
+                };
+                match input.and_then(|input| bench::bench(day, *part, &input, &source, runs).unwrap()) {
+                    Ok(result) => results.push(result),
+                    Err(err) => {
+                        eprintln!("day {:2}, part {}: error: {}", day.day, part, err);
+                        failed += 1;
+                    }
+                }
+            }
+
+            if json { println!("{}", bench::render_json(&results)); }
+            else { print!("{}", bench::render_table(&results)); }
+            if failed > 0 { return Err(CliError::Failed { what: "parts", failed, total: targets.len() }) }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(args: &str) -> Result<Args, CliError> {
+        parse(args.split_whitespace().map(String::from))
+    }
+
+    fn command(args: &str) -> Command { parse_str(args).unwrap().command }
+
+    #[test]
+    fn run_selection_and_input() {
+        assert_eq!(command("run 14 2"), Command::Run {
+            selection: Selection::Day { day: 14, part: Some(2) }, source: InputSource::Real
+        });
+        assert_eq!(command("run 3 --input test1"), Command::Run {
+            selection: Selection::Day { day: 3, part: None }, source: InputSource::variant("test1")
+        });
+        assert_eq!(command("run all --file in.txt"), Command::Run {
+            selection: Selection::All, source: InputSource::File(PathBuf::from("in.txt"))
+        });
+        assert_eq!(command("run 6 1 --file -"), Command::Run {
+            selection: Selection::Day { day: 6, part: Some(1) }, source: InputSource::Stdin
+        });
+        assert_eq!(command("run 6 --input real"), Command::Run {
+            selection: Selection::Day { day: 6, part: None }, source: InputSource::Real
+        });
+    }
+
+    #[test]
+    fn list_check_bench_and_help() {
+        assert_eq!(command("list"), Command::List);
+        assert_eq!(command("check"), Command::Check { day: None, manifest: PathBuf::from(check::MANIFEST_PATH) });
+        assert_eq!(command("check 5 --manifest other.toml"), Command::Check {
+            day: Some(5), manifest: PathBuf::from("other.toml")
+        });
+        assert_eq!(command("bench all"), Command::Bench {
+            selection: Selection::All, source: InputSource::Real, runs: 10, json: false
+        });
+        assert_eq!(command("bench 15 2 --runs 3 --json --input test"), Command::Bench {
+            selection: Selection::Day { day: 15, part: Some(2) }, source: InputSource::variant("test"), runs: 3,
+            json: true
+        });
+        for args in ["", "help", "-h", "--help"] {
+            assert_eq!(command(args), Command::Help, "'{}'", args);
+        }
+    }
+
+    #[test]
+    fn verbosity_anywhere() {
+        assert_eq!(parse_str("run 1").unwrap().verbosity, 0);
+        assert_eq!(parse_str("-v run 1").unwrap().verbosity, 1);
+        assert_eq!(parse_str("run -vv 1 --verbose").unwrap().verbosity, 3);
+        assert_eq!(parse_str("-v").unwrap(), Args { command: Command::Help, verbosity: 1 });
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(parse_str("run"), Err(CliError::MissingArgument("day"))));
+        assert!(matches!(parse_str("bench"), Err(CliError::MissingArgument("day"))));
+        assert!(matches!(parse_str("run 1 --input"), Err(CliError::MissingArgument("input variant"))));
+        assert!(matches!(parse_str("run 1 --file"), Err(CliError::MissingArgument("input file"))));
+        assert!(matches!(parse_str("check --manifest"), Err(CliError::MissingArgument("manifest path"))));
+        assert!(matches!(parse_str("bench 1 --runs"), Err(CliError::MissingArgument("number of runs"))));
+        assert!(matches!(
+            parse_str("bench 1 --runs 0"), Err(CliError::InvalidNumber { what: "number of runs", .. })
+        ));
+        assert!(matches!(parse_str("run x"), Err(CliError::InvalidNumber { what: "day", .. })));
+        assert!(matches!(parse_str("run 1 y"), Err(CliError::InvalidNumber { what: "part", .. })));
+        assert!(matches!(parse_str("check 300"), Err(CliError::InvalidNumber { what: "day", .. })));
+        assert!(matches!(parse_str("run 1 2 3"), Err(CliError::UnexpectedArgument(arg)) if arg == "3"));
+        assert!(matches!(parse_str("list 1"), Err(CliError::UnexpectedArgument(arg)) if arg == "1"));
+        assert!(matches!(parse_str("walk 1"), Err(CliError::UnknownCommand(cmd)) if cmd == "walk"));
+    }
+
+    #[test]
+    fn resolve_against_the_registry() {
+        assert_eq!(resolve(&Selection::Day { day: 1, part: None }).unwrap().len(), 2);
+        assert_eq!(resolve(&Selection::All).unwrap().len(), registry::DAYS.len() * 2);
+        assert!(matches!(resolve(&Selection::Day { day: 30, part: None }), Err(CliError::UnknownDay(30))));
+        assert!(matches!(
+            resolve(&Selection::Day { day: 1, part: Some(3) }), Err(CliError::UnknownPart { day: 1, part: 3 })
+        ));
+    }
+}
@@ -1,30 +1,36 @@
-use crate::read_lines;
+use advent_of_code::parsing::{Input, InputError, Lines};
+use advent_of_code::solution::{Answer, Solution};
 
-fn read() -> Vec<i32> {
-    read_lines("data/day1.txt")
-        .map(|line| line.parse::<i32>().unwrap())
-        .collect::<Vec<_>>()
+fn read(lines: Lines) -> Result<Vec<i32>, InputError> {
+    lines
+        .map(|line| line.parse_token::<i32>(line.as_str(), "a number"))
+        .collect()
 }
 
-/// https://adventofcode.com/2021/day/1
-pub fn part1() {
-    let lines = read();
-    let tupled = lines.iter().zip(lines.iter().skip(1));
-    let increased =
-        tupled.filter(|(v1, v2)| v2 > v1).count();
-    println!("{}", increased)
-}
+pub struct Day1;
+impl Solution for Day1 {
+    type Parsed = Vec<i32>;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError> { read(input.lines()) }
 
-// https://adventofcode.com/2021/day/1#part2
-pub fn part2() {
-    let lines = read();
-    let window_values =
-        lines.iter().zip(lines.iter().skip(1)).zip(lines.iter().skip(2))
-            .map(|((a, b), c)| a + b + c)
-            .collect::<Vec<_>>();
-    let tupled =
-        window_values.iter().zip(window_values.iter().skip(1));
-    let increased =
-        tupled.filter(|(v1, v2)| v2 > v1).count();
-    println!("{}", increased)
-}
\ No newline at end of file
+    /// https://adventofcode.com/2021/day/1
+    fn part1(&self, lines: &Self::Parsed) -> Answer {
+        let tupled = lines.iter().zip(lines.iter().skip(1));
+        let increased =
+            tupled.filter(|(v1, v2)| v2 > v1).count();
+        increased.into()
+    }
+
+    // https://adventofcode.com/2021/day/1#part2
+    fn part2(&self, lines: &Self::Parsed) -> Answer {
+        let window_values =
+            lines.iter().zip(lines.iter().skip(1)).zip(lines.iter().skip(2))
+                .map(|((a, b), c)| a + b + c)
+                .collect::<Vec<_>>();
+        let tupled =
+            window_values.iter().zip(window_values.iter().skip(1));
+        let increased =
+            tupled.filter(|(v1, v2)| v2 > v1).count();
+        increased.into()
+    }
+}
@@ -1,7 +1,8 @@
-use std::collections::HashMap;
-use itertools::{Chunk, Itertools};
+use itertools::Itertools;
+use tracing::trace;
 use crate::day10::ChunkKind::{Bracket, Curly, Fish, Parenthesis};
-use crate::read_lines;
+use advent_of_code::parsing::{Input, InputError, Lines};
+use advent_of_code::solution::{Answer, Solution};
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 enum ChunkKind { Curly, Parenthesis, Bracket, Fish }
@@ -58,7 +59,7 @@ impl Symbol {
     }
 }
 
-struct InputLine {
+pub struct InputLine {
     symbols: Vec<Symbol>
 }
 impl InputLine {
@@ -77,42 +78,47 @@ impl InputLine {
             else {
                 let matches =
                     open_stack.pop()
-                        .is_some_with(|last_opened| *last_opened == symbol.kind);
+                        .is_some_and(|last_opened| last_opened == symbol.kind);
                 if !matches { return Err(symbol.kind) }
             }
         }
 
         for open in open_stack.iter().rev() {
-            // print!("{}", Symbol { kind: *open, opening: false}.to_char());
             score = score * 5 + open.autocomplete_points();
         }
-        // println!(" = {}", score);
+        trace!(
+            completion = %open_stack.iter().rev().map(|open| Symbol { kind: *open, opening: false }.to_char()).join(""),
+            score
+        );
 
         Ok(score)
     }
 }
 
-fn read() -> impl Iterator<Item = InputLine> {
-    read_lines("data/day10.txt").map(|line| {
-        let symbols =
-            line.chars().map(|c| Symbol::parse(c).unwrap()).collect_vec();
-        InputLine { symbols }
-    })
+fn read(lines: Lines) -> Result<Vec<InputLine>, InputError> {
+    lines.map(|line| {
+        Ok(InputLine { symbols: line.chars_with(Symbol::parse, "one of '(){}[]<>'")? })
+    }).collect()
 }
 
-pub fn part1() {
-    let lines = read();
-    let wrong: Vec<ChunkKind> = lines.flat_map(|line| line.find_first_incorrect()).collect();
-    let result: usize = wrong.iter().map(|k| k.syntax_points()).sum();
-    println!("result={}", result);
-}
+pub struct Day10;
+impl Solution for Day10 {
+    type Parsed = Vec<InputLine>;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError> { read(input.lines()) }
+
+    fn part1(&self, lines: &Self::Parsed) -> Answer {
+        let wrong: Vec<ChunkKind> = lines.iter().flat_map(|line| line.find_first_incorrect()).collect();
+        let result: usize = wrong.iter().map(|k| k.syntax_points()).sum();
+        result.into()
+    }
 
-pub fn part2() {
-    let lines = read();
-    let autocompleted: Vec<usize> =
-        lines.flat_map(|line| line.autocomplete().ok()).collect();
-    let sorted = autocompleted.iter().sorted().collect_vec();
-    // println!("sorted={:?}", sorted);
-    let result = sorted[sorted.len() / 2];
-    println!("result={}", result);
-}
\ No newline at end of file
+    fn part2(&self, lines: &Self::Parsed) -> Answer {
+        let autocompleted: Vec<usize> =
+            lines.iter().flat_map(|line| line.autocomplete().ok()).collect();
+        let sorted = autocompleted.iter().sorted().collect_vec();
+        trace!(?sorted);
+        let result = *sorted[sorted.len() / 2];
+        result.into()
+    }
+}
@@ -1,70 +1,55 @@
-use std::collections::HashSet;
 use itertools::Itertools;
-use crate::day5::Vec2;
-use crate::day9::{GridMap, Point};
-use crate::read_lines;
-
-fn step_one(data: &mut GridMap<u32>, flashed: &mut HashSet<Vec2>, c: &Vec2) {
-    if let Some(p) = data.get_point_mut(&c) {
-        *p.value += 1;
-
-        if *p.value > 9 && !flashed.contains(c) {
-            flashed.insert(p.coords);
-
-            for neighbor_coord in p.coords.adjacent(true) {
-                step_one(data, flashed, &neighbor_coord);
-            }
-        }
-    }
+use tracing::{debug, trace};
+use advent_of_code::automaton::Automaton;
+use advent_of_code::flood_fill::Connectivity;
+use advent_of_code::grid::GridMap;
+use advent_of_code::parsing::{Input, InputError, Lines};
+use advent_of_code::render::{Color, Render};
+use advent_of_code::solution::{Answer, Solution};
+
+/// Every step raises the energy of each octopus by one. Octopuses above 9 flash, raising the
+/// energy of all 8 neighbours, and are back at 0 after the step.
+fn octopuses(map: &GridMap<u32>) -> Automaton<u32> {
+    Automaton::new(map.clone(), Connectivity::Eight, |map, c| map.get(&c).unwrap() + 1)
+        .with_cascade(|energy| *energy > 9, |energy| *energy += 1, |energy| *energy = 0)
 }
 
-fn step(data: &mut GridMap<u32>) -> usize {
-    let coords = data.each_coord().collect_vec();
-    let mut flashed = HashSet::<Vec2>::new();
-
-    for c in coords {
-        step_one(data, &mut flashed, &c);
-    }
-
-    for c in &flashed {
-        *data.get_point_mut(c).unwrap().value = 0;
-    }
-
-    flashed.len()
+/// Colours the octopuses that flashed in the last step by the wave they flashed in.
+fn render_waves(octopuses: &Automaton<u32>) -> String {
+    octopuses.waves().zip(Color::ALL.iter().cycle())
+        .fold(Render::new(octopuses.grid()), |render, (wave, color)| render.highlight(wave.iter().copied(), *color))
+        .text(|energy| energy.unwrap().to_string())
 }
 
-fn read() -> GridMap<u32> {
-    let data = read_lines("data/day11.txt").map(|line|
-        line.chars().map(|c| c.to_digit(10).unwrap()).collect_vec()
-    ).collect_vec();
-    GridMap::new_with_data(data)
+fn read(lines: Lines) -> Result<GridMap<u32>, InputError> {
+    Ok(GridMap::new_with_data(lines.digit_grid()?))
 }
 
-pub fn part1() {
-    let mut map = read();
-    // println!("Step #0:");
-    // println!("{}", map);
+pub struct Day11;
+impl Solution for Day11 {
+    type Parsed = GridMap<u32>;
 
-    let mut flashes = 0;
-    for idx in 0..100 {
-        flashes += step(&mut map);
-        println!("Step #{} (flashes={}):\n{}", idx + 1, flashes, "-");
-    }
-    println!("Total flashes={}", flashes);
-}
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError> { read(input.lines()) }
 
-pub fn part2() {
-    let mut map = read();
+    fn part1(&self, map: &Self::Parsed) -> Answer {
+        let mut octopuses = octopuses(map);
+        trace!("Step #0:\n{}", octopuses.grid());
 
-    let mut step_idx = 0usize;
-    loop {
-        step(&mut map);
-        if map.each_point().all(|p| *p.value == 0) {
-            break
-        }
-        else {
-            step_idx += 1;
+        let mut flashes = 0;
+        for _ in 0..100 {
+            let stats = octopuses.step();
+            flashes += stats.fired;
+            debug!(step = stats.step, flashes, waves = octopuses.waves().count());
+            trace!(order = %octopuses.fired().iter().join(" "));
+            trace!("Step #{}:\n{}", stats.step, render_waves(&octopuses));
         }
+        flashes.into()
+    }
+
+    fn part2(&self, map: &Self::Parsed) -> Answer {
+        let cells = map.values().len();
+        let synchronized = octopuses(map).first_step(|stats, _| stats.fired == cells);
+        debug!(?synchronized);
+        synchronized.expect("the octopuses to synchronize").into()
     }
-    println!("at={}", step_idx + 1);
-}
\ No newline at end of file
+}
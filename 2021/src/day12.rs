@@ -1,10 +1,12 @@
 use alloc::rc::Rc;
 use core::fmt::{Debug, Display, Formatter};
 use im_rc::{HashMap, HashSet, Vector};
-use crate::read_lines;
+use tracing::{debug, trace};
+use advent_of_code::parsing::{Input, InputError, Lines};
+use advent_of_code::solution::{Answer, Solution};
 
 #[derive(Hash, Eq, PartialEq, Debug)]
-struct Node {
+pub struct Node {
     name: String,
     is_large_cave: bool
 }
@@ -71,7 +73,7 @@ impl AllowVisitingSmallCaveTwice {
     }
 }
 
-struct Graph {
+pub struct Graph {
     by_name: HashMap<String, Rc<Node>>,
     data: HashMap<Rc<Node>, HashSet<Rc<Node>>>
 }
@@ -105,10 +107,10 @@ impl Graph {
         let mut next_path = current_path.clone();
         next_path.add(current_node);
 
-        // println!("all_paths: {}, {:?}", allow_visiting, next_path);
+        trace!(%allow_visiting, ?next_path, "all_paths");
 
         let mut arrived = || {
-            // println!("No where to go, path={:?}", &next_path);
+            trace!(path = ?next_path, "No where to go");
             if current_node == target_node {
                 paths.push_back(next_path.clone());
             }
@@ -124,13 +126,13 @@ impl Graph {
                 None => arrived(),
                 Some(possible) => {
                     for next in possible.iter() {
-                        if current_path.start().contains(&next) {
-                            // println!("Not going into the start node '{}'", next.name);
+                        if current_path.start() == Some(next) {
+                            trace!(next = %next.name, "Not going into the start node");
                             continue;
                         }
 
                         let mut recurse = |allow_visiting: AllowVisitingSmallCaveTwice| {
-                            // println!("all_paths: next: {:?}", next.name);
+                            trace!(next = ?next.name, "all_paths");
                             let other_paths = self.all_paths(
                                 &next_path, next, target_node,
                                 allow_visiting
@@ -165,42 +167,47 @@ impl Graph {
     }
 }
 
-fn read() -> Graph {
+fn read(lines: Lines) -> Result<Graph, InputError> {
     let mut graph = Graph::new();
-    for line in read_lines("data/day12.txt") {
-        let (from, to) = line.split_once("-").unwrap();
+    for line in lines {
+        let [from, to] = line.extract("{}-{}")?;
         graph.insert(from, to);
         graph.insert(to, from);
     }
-    graph
+    Ok(graph)
 }
 
-pub fn part1() {
-    let graph = read();
-    let start = graph.get("start").unwrap();
-    let end = graph.get("end").unwrap();
-    let paths = graph.all_paths(
-        &Path::new(), start, end,
-        AllowVisitingSmallCaveTwice::Disallow
-    );
-
-    println!("Result ({}):", paths.len());
-    for path in paths {
-        println!("{:?}", path);
+pub struct Day12;
+impl Solution for Day12 {
+    type Parsed = Graph;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError> { read(input.lines()) }
+
+    fn part1(&self, graph: &Self::Parsed) -> Answer {
+        let start = graph.get("start").unwrap();
+        let end = graph.get("end").unwrap();
+        let paths = graph.all_paths(
+            &Path::new(), start, end,
+            AllowVisitingSmallCaveTwice::Disallow
+        );
+
+        for path in &paths {
+            debug!(?path);
+        }
+        paths.len().into()
     }
-}
 
-pub fn part2() {
-    let graph = read();
-    let start = graph.get("start").unwrap();
-    let end = graph.get("end").unwrap();
-    let paths = graph.all_paths(
-        &Path::new(), start, end,
-        AllowVisitingSmallCaveTwice::Allow { visited: None }
-    );
-
-    println!("Result ({}):", paths.len());
-    for path in paths {
-        println!("{:?}", path);
+    fn part2(&self, graph: &Self::Parsed) -> Answer {
+        let start = graph.get("start").unwrap();
+        let end = graph.get("end").unwrap();
+        let paths = graph.all_paths(
+            &Path::new(), start, end,
+            AllowVisitingSmallCaveTwice::Allow { visited: None }
+        );
+
+        for path in &paths {
+            debug!(?path);
+        }
+        paths.len().into()
     }
-}
\ No newline at end of file
+}
@@ -1,12 +1,14 @@
 use core::fmt::{Display, Formatter};
-use std::mem;
 use std::ops::Add;
-use crate::day5::Vec2;
-use crate::day9::GridMap;
-use crate::read_lines;
+use itertools::Itertools;
+use tracing::{debug, trace};
+use advent_of_code::geometry::Vec2;
+use advent_of_code::grid::{Axis, GridMap};
+use advent_of_code::parsing::{Input, InputError, Lines};
+use advent_of_code::solution::{Answer, Solution};
 
 #[derive(Copy, Clone)]
-struct Dot(bool);
+pub struct Dot(bool);
 impl Add for Dot {
     type Output = Dot;
     fn add(self, rhs: Self) -> Self::Output { Dot(self.0 || rhs.0) }
@@ -18,94 +20,68 @@ impl Display for Dot {
 }
 
 #[derive(Debug)]
-enum FoldAlongAxis { Row, Col }
-#[derive(Debug)]
-struct FoldAlong { axis: FoldAlongAxis, index: usize }
+pub struct FoldAlong { axis: Axis, index: usize }
 
-fn fold(map: &mut GridMap<Dot>, along: &FoldAlong) {
-    match along.axis {
-        FoldAlongAxis::Row => {
-            // let rows = map.data.len();
-            let (to, from) =
-                map.data.split_at_mut(along.index + 1);
-            for from_row_idx in 0..from.len() {
-                let to_row_idx = along.index - 1 - from_row_idx;
-                // println!(
-                //     "rows={}, along.index={}, to_row_idx={}, to.len={}, from_row_idx={}, from.len={}",
-                //     rows, along.index, to_row_idx, to.len(), from_row_idx, from.len()
-                // );
-                let from_row_len = from[from_row_idx].len();
-                for col_idx in 0..from_row_len {
-                    let target = &mut to[to_row_idx][col_idx];
-                    let source = &from[from_row_idx][col_idx];
-                    let result = *target + *source;
-                    // println!("[{}][{}]: {} -> [{}][{}] -> {}", from_row_idx, col_idx, source, to_row_idx, col_idx, result);
-                    *target = result;
-                }
-            }
+fn fold(map: &GridMap<Dot>, along: &FoldAlong) -> GridMap<Dot> {
+    trace!(rows = map.height(), cols = map.width(), ?along);
+    map.fold(along.axis, along.index, |kept, folded| *kept + *folded)
+}
 
-            map.data.resize_with(along.index, || panic!("should not be invoked"));
-        }
-        FoldAlongAxis::Col => {
-            for row in map.data.iter_mut() {
-                let (to, from) = row.split_at_mut(along.index + 1);
-                for from_idx in 0..from.len() {
-                    let to_idx = along.index - 1 - from_idx;
-                    let source = from[from_idx];
-                    let target = &mut to[to_idx];
-                    *target = *target + source;
-                }
+fn read(lines: Lines) -> Result<(GridMap<Dot>, Vec<FoldAlong>), InputError> {
+    let mut sections = lines.sections();
 
-                row.resize_with(along.index, || panic!("should not be invoked"));
-            }
-        }
+    let mut map = GridMap::<Dot>::new();
+    for line in sections.expect_section("the dots")? {
+        let [col, row] = line.extract("{},{}")?;
+        let c = Vec2::new(line.parse_token(row, "a row")?, line.parse_token(col, "a column")?);
+        trace!(dot = %c);
+        map.ensure_indexes(&c, &Dot(false));
+        *map.get_mut(&c).unwrap() = Dot(true);
     }
-}
 
-fn read() -> (GridMap<Dot>, Vec<FoldAlong>) {
-    let mut iter = read_lines("data/day13.txt");
-    let mut reading_coords = true;
-    let mut map = GridMap::<Dot>::new();
-    let mut fold_along = Vec::<FoldAlong>::new();
+    let fold_along = sections.expect_section("the fold instructions")?.map(|line| {
+        let [axis, index] = line.extract("fold along {}={}")?;
+        let axis = match axis {
+            "y" => Axis::Row,
+            "x" => Axis::Col,
+            _ => return Err(line.error(axis, "an axis ('x' or 'y')"))
+        };
+        Ok(FoldAlong { axis, index: line.parse_token(index, "a fold index")? })
+    }).collect::<Result<Vec<_>, _>>()?;
 
-    while let Some(line) = iter.next() {
-        if line.is_empty() {
-            reading_coords = false;
-        }
-        else if reading_coords {
-            let (col_str, row_str) = line.split_once(",").unwrap();
-            let col = col_str.parse::<usize>().unwrap();
-            let row = row_str.parse::<usize>().unwrap();
-            let c = Vec2::new(row, col);
-            // println!("{}", c);
-            map.ensure_indexes(&c, &Dot(false));
-            *map.get_mut(&c).unwrap() = Dot(true);
-        }
-        else {
-            let line = line.get("fold along ".len()..line.len()).unwrap();
-            let (axis_str, index_str) = line.split_once("=").unwrap();
-            let axis = match axis_str {
-                "y" => FoldAlongAxis::Row,
-                "x" => FoldAlongAxis::Col,
-                _ => panic!("Unknown axis: {}", axis_str)
-            };
-            let index = index_str.parse::<usize>().unwrap();
-            let instruction = FoldAlong { axis, index };
-            fold_along.push(instruction);
-        }
-    }
+    Ok((map, fold_along))
+}
 
-    (map, fold_along)
+fn dots(map: &GridMap<Dot>) -> usize {
+    map.each_point().filter(|p| p.value.0).count()
 }
 
-pub fn part1() {
-    let (mut map, fold_alongs) = read();
-    println!("{}", map);
-    println!("{:?}", fold_alongs);
-    for fold_along in &fold_alongs {
-        fold(&mut map, fold_along);
-        println!("{}", map);
-        let dots = map.each_point().filter(|p| p.value.0).count();
-        println!("dots={}", dots);
+/// Renders just the dots, without the row and column numbers `Display for GridMap` adds.
+fn render_dots(map: &GridMap<Dot>) -> String {
+    map.rows().map(|row| row.iter().map(|dot| dot.to_string()).collect::<String>()).join("\n")
+}
+
+pub struct Day13;
+impl Solution for Day13 {
+    type Parsed = (GridMap<Dot>, Vec<FoldAlong>);
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError> { read(input.lines()) }
+
+    fn part1(&self, (map, fold_alongs): &Self::Parsed) -> Answer {
+        trace!("\n{}", map);
+        debug!(?fold_alongs);
+        let map = fold(map, &fold_alongs[0]);
+        trace!("\n{}", map);
+        dots(&map).into()
     }
-}
\ No newline at end of file
+
+    fn part2(&self, (map, fold_alongs): &Self::Parsed) -> Answer {
+        let mut map = map.clone();
+        for fold_along in fold_alongs {
+            map = fold(&map, fold_along);
+            trace!("\n{}", map);
+            debug!(dots = dots(&map));
+        }
+        render_dots(&map).into()
+    }
+}
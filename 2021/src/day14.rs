@@ -1,13 +1,14 @@
 use std::collections::HashMap;
-use std::iter::repeat;
 use itertools::Itertools;
-use crate::read_lines;
+use tracing::{debug, trace};
+use advent_of_code::parsing::{Input, InputError, Lines};
+use advent_of_code::solution::{Answer, Solution};
 
 type Pair = (char, char);
 type Rules = HashMap<Pair, char>;
 
-#[derive(Debug)]
-struct Polymer {
+#[derive(Debug, Clone)]
+pub struct Polymer {
     pairs: HashMap<Pair, usize>,
     counts: HashMap<char, usize>
 }
@@ -21,7 +22,7 @@ impl Polymer {
     fn grow(&mut self, rules: &Rules) {
         let mut new_pairs = HashMap::<Pair, usize>::new();
         for (pair, count) in &self.pairs {
-            match rules.get(&pair) {
+            match rules.get(pair) {
                 None => {
                     *new_pairs.entry(*pair).or_default() += count;
                 }
@@ -40,41 +41,48 @@ impl Polymer {
     }
 }
 
-fn read() -> (Polymer, Rules) {
-    let mut iter = read_lines("data/day14.txt");
-    let polymer = Polymer::from_str(&iter.next().unwrap());
-    iter.next().unwrap();
+fn read(lines: Lines) -> Result<(Polymer, Rules), InputError> {
+    let mut sections = lines.sections();
+    let polymer = Polymer::from_str(sections.expect_section("the polymer template")?.expect_line("the polymer template")?.as_str());
     let rules =
-        Rules::from_iter(iter.map(|line| {
-            let mut line = line.chars();
-            let pair = (line.nth(0).unwrap(), line.nth(0).unwrap());
-            let to = line.nth(4).unwrap();
-            (pair, to)
-        }));
-    (polymer, rules)
+        sections.expect_section("the insertion rules")?.map(|line| {
+            let [pair, to] = line.extract("{} -> {}")?;
+            let [a, b] = line.char_array(pair, "a pair of two elements")?;
+            let [to] = line.char_array(to, "a single element")?;
+            Ok(((a, b), to))
+        }).collect::<Result<Rules, _>>()?;
+    Ok((polymer, rules))
 }
 
-pub fn run(iters: usize) {
-    let (mut polymer, rules) = read();
+fn run(polymer: &Polymer, rules: &Rules, iters: usize) -> usize {
+    let mut polymer = polymer.clone();
 
-    println!("initial: {:?}", polymer);
+    trace!(initial = ?polymer);
     for step in 1..=iters {
-        polymer.grow(&rules);
-        println!("step {}: {:?}", step, polymer);
+        polymer.grow(rules);
+        trace!(step, ?polymer);
     }
 
-    println!("counts: {:?}", polymer.counts);
+    debug!(counts = ?polymer.counts);
 
     let min_count = polymer.counts.iter().min_by_key(|(_, count)| *count).unwrap();
     let max_count = polymer.counts.iter().max_by_key(|(_, count)| *count).unwrap();
     let result = max_count.1 - min_count.1;
-    println!("min={:?}, max={:?}, result={}", min_count, max_count, result);
+    debug!(min = ?min_count, max = ?max_count, result);
+    result
 }
 
-pub fn part1() {
-    run(10);
-}
+pub struct Day14;
+impl Solution for Day14 {
+    type Parsed = (Polymer, Rules);
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError> { read(input.lines()) }
+
+    fn part1(&self, (polymer, rules): &Self::Parsed) -> Answer {
+        run(polymer, rules, 10).into()
+    }
 
-pub fn part2() {
-    run(40);
-}
\ No newline at end of file
+    fn part2(&self, (polymer, rules): &Self::Parsed) -> Answer {
+        run(polymer, rules, 40).into()
+    }
+}
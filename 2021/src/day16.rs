@@ -1,11 +1,13 @@
 use core::fmt::{Debug, Display, Formatter, Write};
 use std::io::Cursor;
-use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+use bitstream_io::{BigEndian, BitRead, BitReader};
 use itertools::Itertools;
 use textwrap::indent;
+use tracing::{debug, trace};
 use crate::day16::OperatorType::{Equal, GreaterThan, LessThan, Max, Min, Product, Sum};
-use crate::day3::bits_bool_to_usize;
-use crate::read_lines;
+use advent_of_code::bits::bits_bool_to_usize;
+use advent_of_code::parsing::{Input, InputError, Lines};
+use advent_of_code::solution::{Answer, Solution};
 
 #[derive(Debug, Copy, Clone)]
 struct PacketVersion(usize);
@@ -53,10 +55,11 @@ enum PacketType {
     Operator(OperatorType)
 }
 impl PacketType {
-    fn new(bits: &[bool]) -> Self {
+    fn new(bits: &[bool]) -> Result<Self, String> {
         match bits_bool_to_usize(&bits[3..6]) {
-            4 => PacketType::Literal,
-            other => PacketType::Operator(OperatorType::new(other).unwrap())
+            4 => Ok(PacketType::Literal),
+            other => OperatorType::new(other).map(PacketType::Operator)
+                .ok_or_else(|| format!("a known packet type id instead of {}", other))
         }
     }
 }
@@ -64,19 +67,20 @@ impl PacketType {
 #[derive(Debug)]
 struct PacketBodyLiteral { value: usize, len_bits: usize }
 impl PacketBodyLiteral {
-    fn read(body: &[bool]) -> Self {
-        // println!("src={}", Bits { bits: Vec::from(slice) });
+    fn read(body: &[bool]) -> Result<Self, String> {
+        trace!(src = %Bits { bits: Vec::from(body) });
         let mut idx = 0usize;
         let mut bits = Vec::<bool>::new();
 
         loop {
+            if body.len() < idx + 5 { return Err(String::from("a complete literal value group")) }
             let last_group = !body[idx];
 
             bits.push(body[idx + 1]);
             bits.push(body[idx + 2]);
             bits.push(body[idx + 3]);
             bits.push(body[idx + 4]);
-            // println!("idx={}, bits={}", idx, Bits { bits: bits.clone() });
+            trace!(idx, bits = %Bits { bits: bits.clone() });
             idx += 5;
 
             if last_group { break }
@@ -85,7 +89,7 @@ impl PacketBodyLiteral {
         let value = bits_bool_to_usize(&bits);
         let len_bits = idx;
         let body = Self { value, len_bits };
-        body
+        Ok(body)
     }
 }
 
@@ -101,18 +105,18 @@ impl PacketBodyOperator {
         &body[(1 + length.bits())..]
     }
 
-    fn read(body: &[bool]) -> Self {
-        let length = OperatorLengthType::parse(body);
-        println!("packet operator body length type: {:?}", length);
+    fn read(body: &[bool]) -> Result<Self, String> {
+        let length = OperatorLengthType::parse(body)?;
+        trace!(?length, "packet operator body length type");
 
         let mut counter = 0usize;
         let mut packets = Vec::<Packet>::new();
         let mut packets_slice = Self::body_without_header(body, &length);
         loop {
-            println!("Reading sub-packet #{}", packets.len());
+            trace!("Reading sub-packet #{}", packets.len());
 
-            let packet = Packet::new(Bits { bits: Vec::from(packets_slice) });
-            println!("Read sub-packet #{}: {}", packets.len(), packet);
+            let packet = Packet::new(Bits { bits: Vec::from(packets_slice) })?;
+            trace!("Read sub-packet #{}: {}", packets.len(), packet);
             let packet_len = packet.len_bits();
             packets.push(packet);
 
@@ -121,18 +125,18 @@ impl PacketBodyOperator {
             match length {
                 OperatorLengthType::TotalLengthInBits { length } => {
                     counter += packet_len;
-                    println!("Read {} bits, a total of {} bits", packet_len, counter);
+                    trace!("Read {} bits, a total of {} bits", packet_len, counter);
                     if counter >= length { break }
                 }
                 OperatorLengthType::NumberOfSubPackets { number } => {
                     counter += 1;
-                    println!("Read a packet, a total of {} packets", counter);
+                    trace!("Read a packet, a total of {} packets", counter);
                     if counter >= number { break }
                 }
             }
         }
 
-        PacketBodyOperator { length, packets }
+        Ok(PacketBodyOperator { length, packets })
     }
 }
 
@@ -149,14 +153,14 @@ impl PacketBody {
         }
     }
 
-    fn new(type_: &PacketType, body: &[bool]) -> PacketBody {
-        println!("Reading packet body");
+    fn new(type_: &PacketType, body: &[bool]) -> Result<PacketBody, String> {
+        trace!("Reading packet body");
         let body = match type_ {
-            PacketType::Literal => PacketBody::Literal(PacketBodyLiteral::read(body)),
-            PacketType::Operator(_) => PacketBody::Operator(PacketBodyOperator::read(body))
+            PacketType::Literal => PacketBody::Literal(PacketBodyLiteral::read(body)?),
+            PacketType::Operator(_) => PacketBody::Operator(PacketBodyOperator::read(body)?)
         };
-        println!("Read packet body: {:?}", body);
-        body
+        trace!(?body, "Read packet body");
+        Ok(body)
     }
 }
 
@@ -173,11 +177,18 @@ impl OperatorLengthType {
         }
     }
 
-    fn parse(body: &[bool]) -> Self {
-        match body[0] {
-            false => Self::TotalLengthInBits { length: bits_bool_to_usize(&body[1..16]) as usize },
-            true => Self::NumberOfSubPackets { number: bits_bool_to_usize(&body[1..12]) as usize },
-        }
+    fn parse(body: &[bool]) -> Result<Self, String> {
+        let length = match body.first() {
+            None => return Err(String::from("an operator length type")),
+            Some(false) => Self::TotalLengthInBits { length: 0 },
+            Some(true) => Self::NumberOfSubPackets { number: 0 }
+        };
+        let value_bits = body.get(1..(1 + length.bits())).ok_or_else(|| format!("{} bits of operator length", length.bits()))?;
+        let value = bits_bool_to_usize(value_bits);
+        Ok(match length {
+            Self::TotalLengthInBits { .. } => Self::TotalLengthInBits { length: value },
+            Self::NumberOfSubPackets { .. } => Self::NumberOfSubPackets { number: value }
+        })
     }
 }
 
@@ -185,8 +196,8 @@ struct Bits {
     bits: Vec<bool>
 }
 impl Bits {
-    fn from_str(s: &str) -> Self {
-        let decoded = hex::decode(s).unwrap();
+    fn from_str(s: &str) -> Result<Self, hex::FromHexError> {
+        let decoded = hex::decode(s)?;
         let cursor = Cursor::new(decoded);
         let mut reader =
             BitReader::endian(cursor, BigEndian);
@@ -194,7 +205,7 @@ impl Bits {
         while let Ok(bit) = reader.read_bit() {
             bits.push(bit);
         }
-        Self { bits }
+        Ok(Self { bits })
     }
 }
 impl Display for Bits {
@@ -207,21 +218,18 @@ impl Display for Bits {
     }
 }
 
-struct Packet {
+pub struct Packet {
     bits: Bits,
     type_: PacketType,
     body: PacketBody
 }
 impl Packet {
-    fn new(bits: Bits) -> Self {
-        let type_ = PacketType::new(&bits.bits);
-        println!("Read packet type: {:?}", type_);
-        let body = PacketBody::new(&type_, Self::body_slice_(&bits.bits));
-        Self { bits, type_, body }
-    }
-
-    fn from_str(s: &str) -> Self {
-        Self::new(Bits::from_str(s))
+    fn new(bits: Bits) -> Result<Self, String> {
+        if bits.bits.len() < 6 { return Err(String::from("a packet header")) }
+        let type_ = PacketType::new(&bits.bits)?;
+        trace!(?type_, "Read packet type");
+        let body = PacketBody::new(&type_, Self::body_slice_(&bits.bits))?;
+        Ok(Self { bits, type_, body })
     }
 
     fn len_bits(&self) -> usize {
@@ -249,7 +257,7 @@ impl Packet {
     }
 
     fn recursive<'a, A : 'a>(&'a self, f: &'a impl Fn(&Packet) -> A) -> Vec<A> {
-        vec![self].iter().flat_map(|p| {
+        [self].iter().flat_map(|p| {
             let mut v = vec![f(p)];
             match &p.body {
                 PacketBody::Literal(_) => {}
@@ -280,25 +288,41 @@ impl Debug for Packet {
     }
 }
 
-fn read() -> Vec<(String, Packet)> {
-    read_lines("data/day16.txt").map(|line| {
-        println!("Parsing {}", line);
-        let packet = Packet::from_str(line.as_str());
-        (line, packet)
-    }).collect_vec()
+fn read(lines: Lines) -> Result<Vec<(String, Packet)>, InputError> {
+    lines.map(|line| {
+        let source = line.as_str();
+        debug!(source, "Parsing");
+        let bits = Bits::from_str(source).map_err(|err| match err {
+            hex::FromHexError::InvalidHexCharacter { index, .. } =>
+                line.error_at_char(source, index, "a hexadecimal digit"),
+            _ => line.error(source, "an even number of hexadecimal digits")
+        })?;
+        let packet = Packet::new(bits).map_err(|expected| line.error(source, expected))?;
+        Ok((line.text.clone(), packet))
+    }).collect()
 }
 
-pub fn part1() {
-    let all_bits = read();
-    for (source, packet) in all_bits {
-        let versions_sum =
-            packet.recursive(&|p| p.version()).iter().map(|v| v.0)
-                .sum::<usize>();
-
-        println!("##### {}", source);
-        println!("{}", packet);
-        println!("versions_sum={}", versions_sum);
-        println!("value={}", packet.value());
-        println!("#####\n\n");
+/// Every input line is a separate transmission, the answers are summed over all of them.
+pub struct Day16;
+impl Solution for Day16 {
+    type Parsed = Vec<(String, Packet)>;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError> { read(input.lines()) }
+
+    fn part1(&self, packets: &Self::Parsed) -> Answer {
+        let mut result = 0usize;
+        for (source, packet) in packets {
+            let versions_sum =
+                packet.recursive(&|p| p.version()).iter().map(|v| v.0)
+                    .sum::<usize>();
+
+            debug!(source, versions_sum, "\n{}", packet);
+            result += versions_sum;
+        }
+        result.into()
     }
-}
\ No newline at end of file
+
+    fn part2(&self, packets: &Self::Parsed) -> Answer {
+        packets.iter().map(|(_, packet)| packet.value()).sum::<usize>().into()
+    }
+}
@@ -1,120 +1,52 @@
 use core::fmt::{Display, Formatter, Write};
-use std::cmp::Ordering;
-use crate::day5::Vec2;
-use crate::day9::{GridMap, Offset};
-use crate::read_lines;
-
-#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
-pub struct Vec2Signed {
-    /// aka row
-    pub x: i64,
-    /// aka col
-    pub y: i64
-}
-impl Vec2Signed {
-    pub fn inverse_x(&self) -> Self {
-        Self { x: -self.x, y: self.y }
-    }
+use tracing::{debug, enabled, trace, Level};
+use advent_of_code::geometry::{Rect, Vec2Signed};
+use advent_of_code::grid::InfiniteGrid;
+use advent_of_code::parsing::{Input, InputError, Lines};
+use advent_of_code::solution::{Answer, Solution};
 
-    pub fn inverse_y(&self) -> Self {
-        Self { x: self.x, y: -self.y }
-    }
-}
-impl PartialOrd<Vec2Signed> for Vec2Signed {
-    fn partial_cmp(&self, other: &Vec2Signed) -> Option<Ordering> {
-        if self.x == other.x && self.y == other.y { Some(Ordering::Equal) }
-        else if self.x > other.x && self.y > other.y { Some(Ordering::Greater) }
-        else if self.y < other.x && self.y < other.y { Some(Ordering::Less) }
-        else { None }
-    }
-}
-
-impl Vec2Signed {
-    pub fn new(x: i64, y: i64) -> Self {
-        Self { x, y }
-    }
-
-    pub fn to_vec2(&self, offset: &Offset) -> Vec2 {
-        offset.map(self.x, self.y)
-    }
-}
-impl Display for Vec2Signed {
-    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        f.write_fmt(format_args!("({},{})", self.x, self.y))
-    }
-}
-
-struct TargetArea {
-    from: Vec2Signed,
-    to: Vec2Signed
-}
+pub struct TargetArea(Rect);
 
 impl TargetArea {
     pub(crate) fn inverse_x(&self) -> Self {
-        Self { from: self.from.inverse_x(), to: self.to.inverse_x() }
+        Self(Rect::new(self.0.min.inverse_x(), self.0.max.inverse_x()))
     }
 
-    pub(crate) fn within(&self, c: &Vec2Signed) -> bool {
-        c >= &self.from && c <= &self.to
-    }
+    pub(crate) fn within(&self, c: &Vec2Signed) -> bool { self.0.contains(c) }
 
+    /// Whether a probe at `c` can no longer reach the target area: it is either past it or below it.
     pub(crate) fn missed(&self, c: &Vec2Signed) -> bool {
-        c.y > self.to.y
-    }
-
-    fn each_coord(&self) -> impl Iterator<Item = Vec2Signed> + '_ {
-        (self.from.x..=self.to.x).flat_map(|row| {
-            (self.from.y..=self.to.y).map(move |col| {
-                Vec2Signed::new(row, col)
-            })
-        })
+        // Probes only ever fall (rows decrease) and drift forward (columns increase).
+        self.0.is_past(c, Vec2Signed::new(-1, 1))
     }
 }
 impl Display for TargetArea {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        f.write_fmt(format_args!("[from={}, to={}]", self.from, self.to))
+        f.write_fmt(format_args!("[from={}, to={}]", self.0.min, self.0.max))
     }
 }
 
-fn read_coords() -> (Offset, TargetArea) {
+fn read_coords(mut lines: Lines) -> Result<TargetArea, InputError> {
     // target area: x=20..30, y=-10..-5
-    let line = read_lines("data/day17_test.txt").next().unwrap();
-    let xy = line.split_once(":").unwrap().1.trim();
-    let (x, y) = xy.split_once(", ").unwrap();
-    let (x_from, x_to) = x.split_once("=").unwrap().1.split_once("..").unwrap();
-    let (y_from, y_to) = y.split_once("=").unwrap().1.split_once("..").unwrap();
-
-    let col_from = x_from.parse::<i64>().unwrap();
-    let col_to = x_to.parse::<i64>().unwrap();
-    let row_from = y_from.parse::<i64>().unwrap();
-    let row_to = y_to.parse::<i64>().unwrap();
-
-    let offset = Offset {
-        x: if row_from < 0 { -row_from } else { 0 } as usize,
-        y: if col_from < 0 { -col_from } else { 0 } as usize
-    };
-
-    let from = Vec2Signed::new(row_from, col_from);
-    let to = Vec2Signed::new(row_to, col_to);
-    let target_area = TargetArea { from, to };
-
-    (offset, target_area)
+    let line = lines.expect_line("a target area")?;
+    let [col_from, col_to, row_from, row_to] =
+        line.parse_tokens::<i64, 4>(line.extract("target area: x={}..{}, y={}..{}")?, "a number")?;
+
+    Ok(TargetArea(Rect::new(Vec2Signed::new(row_from, col_from), Vec2Signed::new(row_to, col_to))))
 }
 
 const SUBMARINE: Vec2Signed = Vec2Signed::new(0, 0);
 
-fn to_grid(offset: Offset, target_area: &TargetArea) -> GridMap<Tile> {
-    let mut map = GridMap::new_with_offset(offset);
-    map.ensure_indexes_offset(&SUBMARINE, &Tile::Empty);
-    *map.get_mut_offset(&SUBMARINE).unwrap() = Tile::Submarine;
+fn to_grid(target_area: &TargetArea) -> InfiniteGrid<Tile> {
+    let mut map = InfiniteGrid::new(Tile::Empty).rows_up();
+    map.set(&SUBMARINE, Tile::Submarine);
 
-    map.ensure_indexes_offset(&target_area.to, &Tile::Empty);
     let inversed_target_area = target_area.inverse_x();
-    map.ensure_indexes_offset(&inversed_target_area.from, &Tile::Empty);
-    map.ensure_indexes_offset(&inversed_target_area.to, &Tile::Empty);
+    map.include(&inversed_target_area.0.min);
+    map.include(&inversed_target_area.0.max);
 
-    for coord in target_area.each_coord() {
-        *map.get_mut_offset(&coord).unwrap() = Tile::Target;
+    for coord in target_area.0.points() {
+        map.set(&coord, Tile::Target);
     }
     map
 }
@@ -132,18 +64,61 @@ impl Display for Tile {
     }
 }
 
-fn shoot(map: &mut GridMap<Tile>, target_area: &TargetArea, shoot_vector: &Vec2Signed) {
-    let mut pos = *shoot_vector;
+/// Returns the trajectory of the probe if it ends up within the target area.
+fn shoot(target_area: &TargetArea, shoot_vector: &Vec2Signed) -> Option<Vec<Vec2Signed>> {
+    let mut pos = SUBMARINE;
     let mut vec = *shoot_vector;
-    while !target_area.within(&pos) && !target_area.missed(&pos) {
-        println!("pos={}", pos);
+    let mut trajectory = vec![pos];
+    while !target_area.missed(&pos) {
+        if target_area.within(&pos) { return Some(trajectory) }
+
+        pos += vec;
+        trajectory.push(pos);
+        vec.x -= 1;
         vec.y_towards_0();
     }
+    None
+}
+
+/// Trajectories of all the initial velocities that hit the target area.
+fn hits(target_area: &TargetArea) -> Vec<Vec<Vec2Signed>> {
+    // Anything faster overshoots the target area in the first step, either vertically on the way
+    // down (after coming back to the submarine's row) or horizontally.
+    let max_up = -target_area.0.min.x;
+    (target_area.0.min.x..=max_up).flat_map(|row| {
+        (0..=target_area.0.max.y).map(move |col| Vec2Signed::new(row, col))
+    }).flat_map(|vec| shoot(target_area, &vec)).collect()
+}
+
+fn draw(map: &mut InfiniteGrid<Tile>, trajectory: &[Vec2Signed]) {
+    for pos in trajectory {
+        let tile = map.get_mut(pos);
+        if let Tile::Empty = tile { *tile = Tile::Shot; }
+    }
+}
+
+fn highest_point(trajectory: &[Vec2Signed]) -> i64 {
+    trajectory.iter().map(|pos| pos.x).max().unwrap()
 }
 
-pub fn part1() {
-    let (offset, target_area) = read_coords();
-    println!("{:?}, target_area={}", offset, target_area);
-    let map = to_grid(offset, &target_area);
-    println!("{}", map);
-}
\ No newline at end of file
+pub struct Day17;
+impl Solution for Day17 {
+    type Parsed = TargetArea;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError> { read_coords(input.lines()) }
+
+    fn part1(&self, target_area: &Self::Parsed) -> Answer {
+        debug!(%target_area);
+        let highest = hits(target_area).into_iter().max_by_key(|t| highest_point(t)).unwrap();
+        if enabled!(Level::TRACE) {
+            let mut map = to_grid(target_area);
+            draw(&mut map, &highest);
+            trace!("\n{}", map);
+        }
+        highest_point(&highest).into()
+    }
+
+    fn part2(&self, target_area: &Self::Parsed) -> Answer {
+        hits(target_area).len().into()
+    }
+}
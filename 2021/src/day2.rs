@@ -1,60 +1,65 @@
-use std::fs::File;
-use std::io::{BufReader, Lines};
-use std::iter::Map;
-use crate::read_lines;
+use advent_of_code::parsing::{Input, InputError, Lines};
+use advent_of_code::solution::{Answer, Solution};
 
-fn read() -> Map<Lines<BufReader<File>>, fn(std::io::Result<String>) -> String> {
-    read_lines("data/day2.txt")
+#[derive(Debug, Copy, Clone)]
+pub enum Command { Forward, Down, Up }
+
+fn read(lines: Lines) -> Result<Vec<(Command, i32)>, InputError> {
+    lines.map(|line| {
+        let [command, num] = line.extract("{} {}")?;
+        let command = match command {
+            "forward" => Command::Forward,
+            "down" => Command::Down,
+            "up" => Command::Up,
+            other => return Err(line.error(other, "one of 'forward', 'down' or 'up'"))
+        };
+        Ok((command, line.parse_token::<i32>(num, "a number")?))
+    }).collect()
 }
 
-/// https://adventofcode.com/2021/day/2
-pub fn part1() {
-    let lines = read();
-    let mut depth = 0;
-    let mut horizontal_position = 0;
-    for line in lines {
-        let mut words = line.split(" ");
-        let command = words.next().unwrap();
-        let num = words.next().unwrap().parse::<i32>().unwrap();
-
-        match command {
-            "forward" => {
-                horizontal_position += num
-            },
-            "down" => {
-                depth += num;
-            },
-            "up" => {
-                depth -= num;
-            },
-            _ => panic!()
-        }
-    }
+pub struct Day2;
+impl Solution for Day2 {
+    type Parsed = Vec<(Command, i32)>;
 
-    println!("{}", depth * horizontal_position);
-}
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError> { read(input.lines()) }
 
-/// https://adventofcode.com/2021/day/2#part2
-pub fn part2() {
-    let lines = read();
-    let mut aim = 0;
-    let mut depth = 0;
-    let mut horizontal_position = 0;
-    for line in lines {
-        let mut words = line.split(" ");
-        let command = words.next().unwrap();
-        let num = words.next().unwrap().parse::<i32>().unwrap();
-
-        match command {
-            "down" => aim += num,
-            "up" => aim -= num,
-            "forward" => {
-                horizontal_position += num;
-                depth += aim * num;
-            },
-            _ => panic!()
+    /// https://adventofcode.com/2021/day/2
+    fn part1(&self, commands: &Self::Parsed) -> Answer {
+        let mut depth = 0;
+        let mut horizontal_position = 0;
+        for (command, num) in commands {
+            match command {
+                Command::Forward => {
+                    horizontal_position += num
+                },
+                Command::Down => {
+                    depth += num;
+                },
+                Command::Up => {
+                    depth -= num;
+                }
+            }
         }
+
+        (depth * horizontal_position).into()
     }
 
-    println!("{}", depth * horizontal_position);
-}
\ No newline at end of file
+    /// https://adventofcode.com/2021/day/2#part2
+    fn part2(&self, commands: &Self::Parsed) -> Answer {
+        let mut aim = 0;
+        let mut depth = 0;
+        let mut horizontal_position = 0;
+        for (command, num) in commands {
+            match command {
+                Command::Down => aim += num,
+                Command::Up => aim -= num,
+                Command::Forward => {
+                    horizontal_position += num;
+                    depth += aim * num;
+                }
+            }
+        }
+
+        (depth * horizontal_position).into()
+    }
+}
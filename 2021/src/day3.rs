@@ -1,7 +1,7 @@
-use std::fs::File;
-use std::io::{BufReader, Lines};
-use std::iter::Map;
-use crate::read_lines;
+use tracing::{debug, trace};
+use advent_of_code::bits::bits_to_u32;
+use advent_of_code::parsing::{Input, InputError, Lines};
+use advent_of_code::solution::{Answer, Solution};
 
 #[derive(Debug)]
 struct Counts {
@@ -26,17 +26,11 @@ impl Counts {
     }
 }
 
-fn read() -> Map<Map<Lines<BufReader<File>>, fn(std::io::Result<String>) -> String>, fn(String) -> Vec<u8>> {
-    read_lines("data/day3.txt").map(|line|
-        line.chars().map(|c| match c {
-            '1' => 1,
-            '0' => 0,
-            _ => panic!()
-        }).collect::<Vec<_>>()
-    )
+fn read(lines: Lines) -> Result<Vec<Vec<u8>>, InputError> {
+    lines.grid_with(|c| c.to_digit(2).map(|bit| bit as u8), "a bit ('0' or '1')")
 }
 
-fn counts_of(lines: &Vec<Vec<u8>>) -> Vec<Counts> {
+fn counts_of(lines: &[Vec<u8>]) -> Vec<Counts> {
     let mut iter = lines.iter();
     let first = iter.next().unwrap();
     let mut counts = first.iter().map(|c| {
@@ -52,57 +46,19 @@ fn counts_of(lines: &Vec<Vec<u8>>) -> Vec<Counts> {
     counts
 }
 
-fn read_counts() -> Vec<Counts> {
-    let lines = read().collect::<Vec<_>>();
-    counts_of(&lines)
-}
-
-pub fn bits_to_u32(slice: &[u8]) -> u32 {
-    slice.iter().rev().enumerate()
-        .map(|(idx, bit)| (*bit as u32) << idx)
-        .fold(0, |a, b| { a | b })
-}
-pub fn bits_bool_to_u32(slice: &[bool]) -> u32 {
-    slice.iter().rev().enumerate()
-        .map(|(idx, bit)| (if *bit { 1 } else { 0 }) << idx)
-        .fold(0, |a, b| { a | b })
-}
-pub fn bits_to_usize(slice: &[u8]) -> usize {
-    slice.iter().rev().enumerate()
-        .map(|(idx, bit)| (*bit as usize) << idx)
-        .fold(0, |a, b| { a | b })
-}
-pub fn bits_bool_to_usize(slice: &[bool]) -> usize {
-    slice.iter().rev().enumerate()
-        .map(|(idx, bit)| (if *bit { 1 } else { 0 }) << idx)
-        .fold(0, |a, b| { a | b })
-}
-
-pub fn part1() {
-    let counts = read_counts();
-    let gamma_bits = counts.iter().map(|c| c.most_common()).collect::<Vec<_>>();
-    let epsilon_bits = counts.iter().map(|c| c.least_common()).collect::<Vec<_>>();
-    let gamma = bits_to_u32(&gamma_bits);
-    let epsilon = bits_to_u32(&epsilon_bits);
-    let result = gamma * epsilon;
-    println!("gamma_bits: {:?}", gamma_bits);
-    println!("gamma: {:?}", gamma);
-    println!("epsilon_bits: {:?}", epsilon_bits);
-    println!("epsilon: {:?}", epsilon);
-    println!("result: {:?}", result);
-}
-
 fn filter(
     mut lines: Vec<Vec<u8>>, bit_criteria: impl Fn(&Counts) -> u8
 ) -> Vec<u8> {
     let range = 0..lines[0].len();
     for idx in range {
-        // println!("idx={}, lines: {:?}", idx, lines);
+        trace!(idx, ?lines, "filtering");
 
         let counts = counts_of(&lines);
-        // println!("counts: {:?}", counts);
-        // println!("most_common: {:?}", counts.iter().map(|c| c.most_common()).collect::<Vec<_>>());
-        // println!("least_common: {:?}", counts.iter().map(|c| c.least_common()).collect::<Vec<_>>());
+        trace!(
+            ?counts,
+            most_common = ?counts.iter().map(|c| c.most_common()).collect::<Vec<_>>(),
+            least_common = ?counts.iter().map(|c| c.least_common()).collect::<Vec<_>>()
+        );
         let bit = bit_criteria(&counts[idx]);
         lines.retain(|line| line[idx] == bit);
         if lines.len() <= 1 { return lines.remove(0) }
@@ -110,24 +66,37 @@ fn filter(
     panic!();
 }
 
-pub fn part2() {
-    let lines = read().collect::<Vec<_>>();
+pub struct Day3;
+impl Solution for Day3 {
+    type Parsed = Vec<Vec<u8>>;
 
-    println!("oxygen");
-    let oxygen_generator_rating_bits =
-        filter(lines.clone(), |c| c.most_common());
-    let oxygen_generator_rating = bits_to_u32(&oxygen_generator_rating_bits);
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError> { read(input.lines()) }
+
+    fn part1(&self, lines: &Self::Parsed) -> Answer {
+        let counts = counts_of(lines);
+        let gamma_bits = counts.iter().map(|c| c.most_common()).collect::<Vec<_>>();
+        let epsilon_bits = counts.iter().map(|c| c.least_common()).collect::<Vec<_>>();
+        let gamma = bits_to_u32(&gamma_bits);
+        let epsilon = bits_to_u32(&epsilon_bits);
+        let result = gamma * epsilon;
+        debug!(?gamma_bits, gamma, ?epsilon_bits, epsilon);
+        result.into()
+    }
 
-    println!("co2");
-    let co2_scrubber_rating_bits =
-        filter(lines, |c| c.least_common());
-    let co2_scrubber_rating = bits_to_u32(&co2_scrubber_rating_bits);
+    fn part2(&self, lines: &Self::Parsed) -> Answer {
+        debug!("oxygen");
+        let oxygen_generator_rating_bits =
+            filter(lines.clone(), |c| c.most_common());
+        let oxygen_generator_rating = bits_to_u32(&oxygen_generator_rating_bits);
 
-    let result = oxygen_generator_rating * co2_scrubber_rating;
+        debug!("co2");
+        let co2_scrubber_rating_bits =
+            filter(lines.clone(), |c| c.least_common());
+        let co2_scrubber_rating = bits_to_u32(&co2_scrubber_rating_bits);
 
-    println!("oxygen_generator_rating_bits: {:?}", oxygen_generator_rating_bits);
-    println!("oxygen_generator_rating: {:?}", oxygen_generator_rating);
-    println!("co2_scrubber_rating_bits: {:?}", co2_scrubber_rating_bits);
-    println!("co2_scrubber_rating: {:?}", co2_scrubber_rating);
-    println!("result: {:?}", result);
-}
\ No newline at end of file
+        let result = oxygen_generator_rating * co2_scrubber_rating;
+
+        debug!(?oxygen_generator_rating_bits, oxygen_generator_rating, ?co2_scrubber_rating_bits, co2_scrubber_rating);
+        result.into()
+    }
+}
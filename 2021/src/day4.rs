@@ -1,10 +1,8 @@
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{BufReader, Lines};
-use std::iter::Map;
-use crate::read_lines;
+use advent_of_code::parsing::{self, InputError, Lines};
+use advent_of_code::solution::{Answer, Solution};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Board {
     numbers: Vec<Vec<u32>>
 }
@@ -30,33 +28,30 @@ impl Board {
     }
 }
 
-struct Input {
+#[derive(Clone)]
+pub struct Input {
     numbers: Vec<u32>,
     boards: Vec<Board>
 }
 
-fn read_board(lines: &mut Map<Lines<BufReader<File>>, fn(std::io::Result<String>) -> String>) -> Board {
+fn read_board(mut lines: Lines) -> Result<Board, InputError> {
     let mut board = Vec::<Vec<u32>>::new();
     for _ in 0..5 {
-        let line =
-            lines.next().unwrap().split_whitespace()
-                .map(|s| s.parse::<u32>().unwrap()).collect::<Vec<_>>();
-        board.push(line);
+        let line = lines.expect_line("a board row")?;
+        let row = line.list::<u32>(line.as_str(), " ", "a number")?;
+        if row.len() != 5 {
+            return Err(line.error_at(1, "a board row of 5 numbers", Some(format!("{} numbers", row.len()))))
+        }
+        board.push(row);
     }
-    Board { numbers: board }
+    lines.expect_end("an empty line between boards")?;
+    Ok(Board { numbers: board })
 }
-
-fn read() -> Input {
-    let mut lines = read_lines("data/day4.txt");
-    let numbers =
-        lines.next().unwrap().split(",")
-            .map(|n| n.parse::<u32>().unwrap())
-            .collect::<Vec<_>>();
-    let mut boards = Vec::<Board>::new();
-    while lines.next().is_some() {
-        boards.push(read_board(&mut lines));
-    }
-    Input { numbers, boards }
+fn read(lines: Lines) -> Result<Input, InputError> {
+    let mut sections = lines.sections();
+    let numbers = parsing::read_num_line(sections.expect_section("the drawn numbers")?)?;
+    let boards = sections.map(read_board).collect::<Result<Vec<_>, _>>()?;
+    Ok(Input { numbers, boards })
 }
 
 fn determine_winning(input: &Input) -> Option<(&Board, HashSet<u32>, u32)> {
@@ -65,7 +60,7 @@ fn determine_winning(input: &Input) -> Option<(&Board, HashSet<u32>, u32)> {
         marked.insert(*num);
         for board in &input.boards {
             if board.check(&marked) {
-                return Some((&board, marked, *num))
+                return Some((board, marked, *num))
             }
         }
     }
@@ -86,7 +81,7 @@ fn filter_winning(mut input: Input) -> Vec<WinningBoard> {
     for num in &input.numbers {
         marked.insert(*num);
         let winning_this_number =
-            input.boards.drain_filter(|board| board.check(&marked));
+            input.boards.extract_if(.., |board| board.check(&marked));
 
         for board in winning_this_number {
             winning_boards.push(WinningBoard {
@@ -98,17 +93,22 @@ fn filter_winning(mut input: Input) -> Vec<WinningBoard> {
     winning_boards
 }
 
-pub fn part1() {
-    let input = read();
-    let (board, marked, num) = determine_winning(&input).unwrap();
-    let score = board.sum_of_unmarked(&marked) * num;
-    println!("score={}", score)
-}
+pub struct Day4;
+impl Solution for Day4 {
+    type Parsed = Input;
+
+    fn parse(&self, input: &parsing::Input) -> Result<Self::Parsed, InputError> { read(input.lines()) }
 
-pub fn part2() {
-    let input = read();
-    let winning_boards = filter_winning(input);
-    let winning_board = winning_boards.last().unwrap();
-    let score = winning_board.board.sum_of_unmarked(&winning_board.numbers) * winning_board.won_at_num;
-    println!("score={}", score)
-}
\ No newline at end of file
+    fn part1(&self, input: &Self::Parsed) -> Answer {
+        let (board, marked, num) = determine_winning(input).unwrap();
+        let score = board.sum_of_unmarked(&marked) * num;
+        score.into()
+    }
+
+    fn part2(&self, input: &Self::Parsed) -> Answer {
+        let winning_boards = filter_winning(input.clone());
+        let winning_board = winning_boards.last().unwrap();
+        let score = winning_board.board.sum_of_unmarked(&winning_board.numbers) * winning_board.won_at_num;
+        score.into()
+    }
+}
@@ -1,144 +1,127 @@
-use core::fmt::{Display, Formatter};
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufReader, Lines};
-use std::iter::Map;
-use crate::read_lines;
-
-#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
-pub struct Vec2 {
-    /// aka row
-    pub x: usize,
-    /// aka col
-    pub y: usize
-}
-
-impl Vec2 {
-    pub fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
-    }
+use itertools::Itertools;
+use tracing::{debug, trace};
+use advent_of_code::geometry::{Raster, Vec2, Vec2Signed};
+use advent_of_code::parsing;
+use advent_of_code::parsing::{Input, InputError, Lines};
+use advent_of_code::render::{Color, Render};
+use advent_of_code::segments::{count_covered, Segment};
+use advent_of_code::solution::{Answer, Solution};
+
+/// How the vent lines are turned into points: the vents only cover the points exactly on a line.
+const RASTER: Raster = Raster::Exact;
 
-    pub fn as_tuple(&self) -> (usize, usize) { (self.x, self.y) }
-
-    pub fn add_x(&self, add: usize) -> Self { Self::new(self.x + add, self.y) }
-    pub fn add_y(&self, add: usize) -> Self { Self::new(self.x, self.y + add) }
-    pub fn add_xy(&self, add_x: usize, add_y: usize) -> Self { Self::new(self.x + add_x, self.y + add_y) }
+#[derive(Debug)]
+pub struct Line { from: Vec2, to: Vec2 }
+impl Line {
+    fn is_axis_aligned(&self) -> bool { self.from.x == self.to.x || self.from.y == self.to.y }
 
-    pub fn x_sub1(&self) -> Option<Self> {
-        self.x.checked_sub(1).map(|x| Self::new(x, self.y))
+    fn segment(&self) -> Segment {
+        Segment { from: Vec2Signed::try_from(self.from).unwrap(), to: Vec2Signed::try_from(self.to).unwrap() }
     }
 
-    pub fn x_plus1(&self) -> Self { self.add_x(1) }
-
-    pub fn y_sub1(&self) -> Option<Self> {
-        self.y.checked_sub(1).map(|y| Self::new(self.x, y))
+    /// Points covered by the line of any slope, from `from` to `to`.
+    fn coordinates(&self, raster: Raster) -> impl Iterator<Item = Vec2> {
+        let segment = self.segment();
+        // Every point lies between the non-negative endpoints.
+        raster.points(segment.from, segment.to).map(|c| Vec2::try_from(c).unwrap())
     }
-
-    pub fn y_plus1(&self) -> Self { self.add_y(1) }
-
-    pub fn x_sub1_y_sub1(&self) -> Option<Self> { self.x_sub1().and_then(|c| c.y_sub1()) }
-    pub fn x_sub1_y_plus1(&self) -> Option<Self> { self.x_sub1().map(|c| c.y_plus1()) }
-    pub fn x_plus1_y_plus1(&self) -> Self { self.x_plus1().y_plus1() }
-    pub fn x_plus1_y_sub1(&self) -> Option<Self> { self.y_sub1().map(|c| c.x_plus1()) }
-
-    pub fn adjacent(&self, diagonals: bool) -> Vec<Vec2> {
-        let mut vec = vec![self.x_plus1(), self.y_plus1()];
-        if let Some(p) = self.x_sub1() { vec.push(p); }
-        if let Some(p) = self.y_sub1() { vec.push(p); }
-        if diagonals {
-            if let Some(p) = self.x_sub1_y_sub1() { vec.push(p); }
-            if let Some(p) = self.x_sub1_y_plus1() { vec.push(p); }
-            if let Some(p) = self.x_plus1_y_sub1() { vec.push(p); }
-            vec.push(self.x_plus1_y_plus1());
-        }
-        vec
-    }
-}
-impl Display for Vec2 {
-    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        f.write_fmt(format_args!("({},{})", self.x, self.y))
-    }
-}
-impl From<Vec2> for (usize, usize) {
-    fn from(v: Vec2) -> Self { v.as_tuple() }
 }
 
-#[derive(Debug)]
-struct Line { from: Vec2, to: Vec2 }
-impl Line {
-    fn coordinates(&self, include_diagonals: bool) -> Vec<Vec2> {
-        let x_range: Vec<_> =
-            if self.from.x < self.to.x { (self.from.x..(self.to.x + 1)).collect() }
-            else { (self.to.x..(self.from.x + 1)).rev().collect() };
-        let y_range: Vec<_> =
-            if self.from.y < self.to.y { (self.from.y..(self.to.y + 1)).collect() }
-            else { (self.to.y..(self.from.y + 1)).rev().collect() };
-        if self.from.x == self.to.x {
-            let x = self.from.x;
-            y_range.iter().map(|y| Vec2::new(x, *y) ).collect()
-        }
-        else if self.from.y == self.to.y {
-            let y = self.from.y;
-            x_range.iter().map(|x| Vec2::new(*x, y) ).collect()
-        }
-        else if include_diagonals {
-            x_range.iter().zip(y_range.iter())
-                .map(|(x, y)| Vec2::new(*x, *y) ).collect()
-        }
-        else {
-            Vec::new()
-        }
-    }
+fn parse_point(line: &parsing::Line, s: &str) -> Result<Vec2, InputError> {
+    let [x, y] = line.parse_tokens(line.extract_in(s, "{},{}")?, "a coordinate")?;
+    // The puzzle's y is the row.
+    Ok(Vec2::new(y, x))
 }
 
-fn parse_point(s: &str) -> Vec2 {
-    let mut elements =
-        s.split(",").map(|s| s.parse::<usize>().unwrap());
-    Vec2::new(elements.next().unwrap(), elements.next().unwrap())
+fn read(lines: Lines) -> Result<Vec<Line>, InputError> {
+    lines.map(|line| {
+        let [from, to] = line.extract("{} -> {}")?;
+        Ok(Line { from: parse_point(&line, from)?, to: parse_point(&line, to)? })
+    }).collect()
 }
 
-fn read() -> Map<Map<Lines<BufReader<File>>, fn(std::io::Result<String>) -> String>, fn(String) -> Line> {
-    read_lines("data/day5.txt").map(|line| {
-        let mut iter = line.split_whitespace();
-        let p1 = parse_point(iter.next().unwrap());
-        iter.next();
-        let p2 = parse_point(iter.next().unwrap());
-        Line { from: p1, to: p2 }
-    })
+/// Renders the vent counts, highlighting the overlaps.
+fn render(map: &HashMap<Vec2, u32>) -> String {
+    let overlaps = map.iter().filter(|(_, cnt)| **cnt > 1).map(|(c, _)| *c);
+    Render::new(map).highlight(overlaps, Color::Red)
+        .text(|entry| entry.map_or(String::from("."), |entry| entry.to_string()))
 }
 
-fn print(map: &HashMap<Vec2, u32>) {
-    let max_x = map.keys().map(|v| v.x).max().unwrap();
-    let max_y = map.keys().map(|v| v.y).max().unwrap();
-    for y in 0..(max_y + 1) {
-        for x in 0..(max_x + 1) {
-            let entry = map.get(&Vec2::new(x, y)).unwrap_or(&0);
-            print!("{}", if *entry == 0 { String::from(".") } else { entry.to_string() })
+/// How the overlaps are counted.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Counting {
+    /// Marks every point of every line in a map.
+    Raster,
+    /// Works with whole lines, see [count_covered], so huge coordinates are fine.
+    Sweep
+}
+impl Counting {
+    fn count(&self, lines: &[&Line]) -> usize {
+        match self {
+            Counting::Raster => count_rasterised(lines),
+            Counting::Sweep => count_swept(lines)
         }
-        println!()
     }
 }
 
-fn run(include_diagonals: bool) {
+fn count_rasterised(lines: &[&Line]) -> usize {
     let mut map = HashMap::<Vec2, u32>::new();
-    for line in read() {
-        // println!("{:?}", line);
-        for point in line.coordinates(include_diagonals) {
-            // println!("{:?}", point);
+    for line in lines {
+        trace!(?line);
+        for point in line.coordinates(RASTER) {
+            trace!(?point);
             let entry = map.entry(point).or_insert(0);
             *entry += 1;
         }
     }
-    // print(&map);
+    trace!("map:\n{}", render(&map));
 
-    let count = map.values().filter(|cnt| **cnt > 1).count();
-    println!("count={}", count);
+    map.values().filter(|cnt| **cnt > 1).count()
 }
 
-pub fn part1() {
-    run(false);
+fn count_swept(lines: &[&Line]) -> usize {
+    let segments = lines.iter().map(|line| line.segment()).collect_vec();
+    count_covered(&segments, 2) as usize
 }
 
-pub fn part2() {
-    run(true);
-}
\ No newline at end of file
+fn run(lines: &[Line], include_sloped: bool, counting: Counting) -> usize {
+    let lines = lines.iter().filter(|line| include_sloped || line.is_axis_aligned()).collect_vec();
+    let count = counting.count(&lines);
+    debug!(?counting, lines = lines.len(), count);
+    count
+}
+
+pub struct Day5 {
+    /// Used by both parts, the registry picks [Counting::Sweep].
+    pub counting: Counting
+}
+impl Solution for Day5 {
+    type Parsed = Vec<Line>;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError> { read(input.lines()) }
+
+    fn part1(&self, lines: &Self::Parsed) -> Answer {
+        run(lines, false, self.counting).into()
+    }
+
+    fn part2(&self, lines: &Self::Parsed) -> Answer {
+        run(lines, true, self.counting).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use advent_of_code::parsing::InputSource;
+    use super::*;
+
+    #[test]
+    fn counting_agrees() {
+        for source in [InputSource::Real, InputSource::variant("test")] {
+            let [raster, sweep] = [Counting::Raster, Counting::Sweep].map(|counting| Day5 { counting });
+            let lines = sweep.parse(&source.load(5).unwrap()).unwrap();
+            assert_eq!(raster.part1(&lines), sweep.part1(&lines), "{:?}", source.path(5));
+            assert_eq!(raster.part2(&lines), sweep.part2(&lines), "{:?}", source.path(5));
+        }
+    }
+}
@@ -1,63 +1,48 @@
-use std::collections::HashMap;
 use itertools::Itertools;
-use crate::{read_lines, read_num_line};
-
-type DaysTillBirth = u8;
-type State = HashMap<DaysTillBirth, usize>;
-
-fn read() -> State {
-    let numbers = read_num_line("data/day6.txt");
-
-    let mut map = State::new();
-    for days_till_birth in numbers {
-        *map.entry(days_till_birth).or_insert(0) += 1;
-    }
-
-    map
+use num_bigint::BigUint;
+use tracing::{debug, trace};
+use advent_of_code::math::{population, Timers};
+use advent_of_code::parsing::{Input, InputError, Lines};
+use advent_of_code::solution::{Answer, Solution};
+
+/// Days until each fish gives birth.
+type Fish = Vec<usize>;
+
+/// A fish whose timer runs out restarts at 6 and has a newborn starting at 8.
+const TIMERS: Timers = Timers::new(6, 8);
+
+fn read(mut lines: Lines) -> Result<Fish, InputError> {
+    let line = lines.expect_line("a comma separated list of timers")?;
+    let expected = format!("a timer from 0 to {}", TIMERS.states() - 1);
+    line.as_str().split(',').map(|token| {
+        let timer = line.parse_token::<usize>(token, &expected)?;
+        if timer < TIMERS.states() { Ok(timer) } else { Err(line.error(token, expected.as_str())) }
+    }).collect()
 }
 
-fn iterate(state: &State) -> State {
-    let mut new_state = State::new();
-
-    for (days_till_birth, count) in state {
-        if *days_till_birth == 0 {
-            *new_state.entry(6).or_insert(0) += *count;
-            *new_state.entry(8).or_insert(0) += *count;
-        }
-        else {
-            *new_state.entry(days_till_birth - 1).or_insert(0) += *count;
-        }
-    }
-
-    new_state
+/// Number of fish with each timer value.
+fn render(fish: &Fish) -> String {
+    fish.iter().counts().into_iter().sorted().map(|(timer, count)| format!("{}={}", timer, count)).join(",")
 }
 
-fn render(state: &State) -> String {
-    state.keys().sorted()
-        .map(|key| format!("{}={}", key, state.get(key).unwrap()))
-        .join(",")
+fn run(fish: &Fish, days: u64) -> BigUint {
+    debug!(?TIMERS, initial = %render(fish));
+    let population = population::<BigUint>(fish, &TIMERS, days);
+    trace!(days, %population);
+    population
 }
 
-fn state_size(state: &State) -> usize {
-    state.values().sum()
-}
+pub struct Day6;
+impl Solution for Day6 {
+    type Parsed = Fish;
 
-fn run(iterations: usize) {
-    let mut state = read();
-    println!("Initial: {}", render(&state));
-    for iteration in 0..iterations {
-        state = iterate(&state);
-        println!("Iteration={}, state size={}", iteration, state_size(&state));
-        // println!("After {} days: {}", iteration + 1, render(&state));
-    }
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError> { read(input.lines()) }
 
-    println!("total={}", state_size(&state));
-}
+    fn part1(&self, fish: &Self::Parsed) -> Answer {
+        run(fish, 80).into()
+    }
 
-pub fn part1() {
-    run(80);
+    fn part2(&self, fish: &Self::Parsed) -> Answer {
+        run(fish, 256).into()
+    }
 }
-
-pub fn part2() {
-    run(256);
-}
\ No newline at end of file
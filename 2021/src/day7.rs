@@ -1,20 +1,22 @@
 use std::collections::HashMap;
-use std::ops::{Add, Sub};
-use itertools::Itertools;
-use crate::{arithmetic_sum, read_num_line};
+use tracing::{debug, trace};
+use advent_of_code::math::arithmetic_sum;
+use advent_of_code::parsing::read_num_line;
+use advent_of_code::parsing::{Input, InputError, Lines};
+use advent_of_code::solution::{Answer, Solution};
 
 type Position = usize;
 type Count = usize;
 type Fuel = usize;
 type State = HashMap<Position, Count>;
 
-fn read() -> State {
-    let positions = read_num_line::<Position>("data/day7.txt");
+fn read(lines: Lines) -> Result<State, InputError> {
+    let positions = read_num_line::<Position>(lines)?;
     let mut state = State::new();
     for position in positions {
         *state.entry(position).or_default() += 1;
     }
-    state
+    Ok(state)
 }
 
 fn fuel_for(state: &State, target_position: &Position, fuel_fn: impl Fn(usize) -> Fuel) -> Fuel {
@@ -26,36 +28,43 @@ fn fuel_for(state: &State, target_position: &Position, fuel_fn: impl Fn(usize) -
     fuel
 }
 
-fn run(fuel_fn: impl Fn(usize) -> Fuel) {
-    let state = read();
+fn run(state: &State, fuel_fn: impl Fn(usize) -> Fuel) -> Fuel {
     let min = *state.keys().min().unwrap();
     let max = *state.keys().max().unwrap();
 
     let mut fuels_at_positions = HashMap::<Position, Fuel>::new();
     for position in min..=max {
-        fuels_at_positions.insert(position, fuel_for(&state, &position, &fuel_fn));
+        fuels_at_positions.insert(position, fuel_for(state, &position, &fuel_fn));
     }
 
-    println!("fuels_at_positions={:?}", fuels_at_positions);
+    trace!(?fuels_at_positions);
 
     let (min_pos, min_fuel) = fuels_at_positions.iter()
         .min_by_key(|(_, fuel)| **fuel)
         .unwrap();
-    println!("min_pos={}, min_fuel={}", min_pos, min_fuel);
+    debug!(min_pos, min_fuel);
+    *min_fuel
 }
 
-pub fn part1() {
-    run(|diff| diff)
-}
+pub struct Day7;
+impl Solution for Day7 {
+    type Parsed = State;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError> { read(input.lines()) }
+
+    fn part1(&self, state: &Self::Parsed) -> Answer {
+        run(state, |diff| diff).into()
+    }
 
-pub fn part2() {
-    run(|diff| {
-        if diff == 0 { 0 }
-        else {
-            match arithmetic_sum(1, diff + 1) {
-                Some(v) => v,
-                None => panic!("Can't calc arithmetic_sum from 1..={}", diff)
+    fn part2(&self, state: &Self::Parsed) -> Answer {
+        run(state, |diff| {
+            if diff == 0 { 0 }
+            else {
+                match arithmetic_sum(1, diff + 1) {
+                    Some(v) => v,
+                    None => panic!("Can't calc arithmetic_sum from 1..={}", diff)
+                }
             }
-        }
-    })
-}
\ No newline at end of file
+        }).into()
+    }
+}
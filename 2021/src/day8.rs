@@ -1,15 +1,13 @@
-use alloc;
-use core::fmt::Write;
 use core::slice::Iter;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
 use std::hash::{Hash, Hasher};
-use std::iter::Map;
-use std::ops::Deref;
 use itertools::Itertools;
 use maplit::{hashmap, hashset};
-use once_cell::sync::Lazy;
-use crate::read_lines;
+use tracing::trace;
+use advent_of_code::parsing;
+use advent_of_code::parsing::{Input, InputError, Lines};
+use advent_of_code::solution::{Answer, Solution};
 
 #[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
 enum Digit { _0 = 0, _1 = 1, _2 = 2, _3 = 3, _4 = 4, _5 = 5, _6 = 6, _7 = 7, _8 = 8, _9 = 9 }
@@ -53,7 +51,7 @@ impl DecoderMap {
 
     fn decode_iter(&self, iter: Iter<DigitWires>) -> DecodedDigits {
         let digits =
-            iter.map(|encoded| self.decode(encoded).unwrap().clone()).collect_vec();
+            iter.map(|encoded| *self.decode(encoded).unwrap()).collect_vec();
         DecodedDigits { digits }
     }
 }
@@ -91,13 +89,15 @@ impl Segment {
         }
     }
 
-    fn parse_chunk(s: &str) -> Option<DigitWires> {
-        let wires: Option<HashSet<Segment>> = s.chars().map(|c| Segment::parse(c)).collect();
+    fn parse_chunk(line: &parsing::Line, s: &str) -> Result<DigitWires, InputError> {
+        let wires: Result<HashSet<Segment>, InputError> = s.char_indices().map(|(idx, c)|
+            Segment::parse(c).ok_or_else(|| line.error_at_char(s, idx, "a segment ('a'..='g')"))
+        ).collect();
         wires.map(|w| DigitWires { wires: w })
     }
 
-    fn parse_line(s: &str) -> Option<Vec<DigitWires>> {
-        s.split_whitespace().map(|chunk| Segment::parse_chunk(chunk)).collect()
+    fn parse_line(line: &parsing::Line, s: &str) -> Result<Vec<DigitWires>, InputError> {
+        s.split_whitespace().map(|chunk| Segment::parse_chunk(line, chunk)).collect()
     }
 }
 
@@ -115,7 +115,7 @@ impl Segment {
 // });
 
 #[derive(Debug)]
-struct Line {
+pub struct Line {
     input_patterns: Vec<DigitWires>,
     outputs: Vec<DigitWires>
 }
@@ -124,27 +124,22 @@ impl Line {
         let grouped =
             self.input_patterns.iter().into_group_map_by(|w| w.wires.len());
 
-        let one = grouped.get(&2).unwrap().get(0).unwrap().wires.clone();
-        let four = grouped.get(&4).unwrap().get(0).unwrap().wires.clone();
-        let seven = grouped.get(&3).unwrap().get(0).unwrap().wires.clone();
-        let eight = grouped.get(&7).unwrap().get(0).unwrap().wires.clone();
-        // println!("1: {:?}", one);
-        // println!("4: {:?}", four);
-        // println!("7: {:?}", seven);
-        // println!("8: {:?}", eight);
+        let one = grouped.get(&2).unwrap().first().unwrap().wires.clone();
+        let four = grouped.get(&4).unwrap().first().unwrap().wires.clone();
+        let seven = grouped.get(&3).unwrap().first().unwrap().wires.clone();
+        let eight = grouped.get(&7).unwrap().first().unwrap().wires.clone();
+        trace!(?one, ?four, ?seven, ?eight, "unique lengths");
 
         let len_5_digits = grouped.get(&5).unwrap();
-        let two_three_five1 = &len_5_digits.get(0).unwrap().wires;
+        let two_three_five1 = &len_5_digits.first().unwrap().wires;
         let two_three_five2 = &len_5_digits.get(1).unwrap().wires;
         let two_three_five3 = &len_5_digits.get(2).unwrap().wires;
 
         let six_or_nine_or_zero_digits = grouped.get(&6).unwrap();
-        let six_or_nine_or_zero1 = &six_or_nine_or_zero_digits.get(0).unwrap().wires;
+        let six_or_nine_or_zero1 = &six_or_nine_or_zero_digits.first().unwrap().wires;
         let six_or_nine_or_zero2 = &six_or_nine_or_zero_digits.get(1).unwrap().wires;
         let six_or_nine_or_zero3 = &six_or_nine_or_zero_digits.get(2).unwrap().wires;
-        // println!("6 | 9 | 0: 1st={:?}", six_or_nine_or_zero1);
-        // println!("6 | 9 | 0: 2nd={:?}", six_or_nine_or_zero2);
-        // println!("6 | 9 | 0: 3rd={:?}", six_or_nine_or_zero3);
+        trace!(?six_or_nine_or_zero1, ?six_or_nine_or_zero2, ?six_or_nine_or_zero3);
 
         let is_six = |digit: &HashSet<Segment>|
             eight.difference(digit).any(|s| one.contains(s));
@@ -158,36 +153,30 @@ impl Line {
             else {
                 (six_or_nine_or_zero3.clone(), six_or_nine_or_zero1, six_or_nine_or_zero2)
             };
-        // println!("6: {:?}", six);
+        trace!(?six);
 
         let is_zero = |digit: &HashSet<Segment>|
             eight.difference(digit).any(|s| four.contains(s));
         let (nine, zero) =
             if is_zero(nine_or_zero1) { (nine_or_zero2.clone(), nine_or_zero1.clone()) }
             else { (nine_or_zero1.clone(), nine_or_zero2.clone()) };
-        // println!("9: {:?}", nine);
-        // println!("0: {:?}", zero);
+        trace!(?nine, ?zero);
 
         let six_or_nine_intersection: HashSet<Segment> =
-            six.intersection(&nine).map(|s| s.clone()).collect();
-        // println!("6 & 9: intersection={:?}", six_or_nine_intersection);
+            six.intersection(&nine).copied().collect();
+        trace!(?six_or_nine_intersection);
         let segment_e =
-            eight.difference(&six_or_nine_intersection)
-                .map(|s| s.clone())
+            *eight.difference(&six_or_nine_intersection)
+                .copied()
                 .collect::<HashSet<Segment>>()
                 .difference(&one)
-                .next().unwrap().clone();
-        // println!("e: {:?}", segment_e);
+                .next().unwrap();
+        trace!(?segment_e);
+        trace!(segment_a = ?seven.difference(&one).next());
 
-        // let segment_a = seven.difference(&one).next().unwrap().clone();
-        // println!("a: {:?}", segment_a);
-
-        let segment_c = nine.difference(&six).next().unwrap().clone();
-        // println!("c: {:?}", segment_c);
-
-        // let segment_f =
-        //     one.difference(&hashset! {segment_c}).next().unwrap().clone();
-        // println!("f: {:?}", segment_f);
+        let segment_c = *nine.difference(&six).next().unwrap();
+        trace!(?segment_c);
+        trace!(segment_f = ?one.difference(&hashset! {segment_c}).next());
 
         let (two, three_five1, three_five2) =
             if two_three_five1.contains(&segment_e) {
@@ -204,16 +193,7 @@ impl Line {
             if three_five1.contains(&segment_c) { (three_five1.clone(), three_five2.clone()) }
             else { (three_five2.clone(), three_five1.clone()) };
 
-        // println!("0: {:?}", zero);
-        // println!("1: {:?}", one);
-        // println!("2: {:?}", two);
-        // println!("3: {:?}", three);
-        // println!("4: {:?}", four);
-        // println!("5: {:?}", five);
-        // println!("6: {:?}", six);
-        // println!("7: {:?}", seven);
-        // println!("8: {:?}", eight);
-        // println!("9: {:?}", nine);
+        trace!(?zero, ?one, ?two, ?three, ?four, ?five, ?six, ?seven, ?eight, ?nine, "decoded digits");
 
         let map = hashmap! {
             DigitWires::new(zero) => Digit::_0,
@@ -231,36 +211,39 @@ impl Line {
     }
 }
 
-fn read() -> Vec<Line> {
-    read_lines("data/day8.txt").map(|line| {
-        let mut iter = line.split(" | ");
-        let input_patterns = Segment::parse_line(iter.next().unwrap()).unwrap();
-        let outputs = Segment::parse_line(iter.next().unwrap()).unwrap();
-        Line { input_patterns, outputs }
+fn read(lines: Lines) -> Result<Vec<Line>, InputError> {
+    lines.map(|line| {
+        let [input_patterns, outputs] = line.extract("{} | {}")?;
+        let input_patterns = Segment::parse_line(&line, input_patterns)?;
+        let outputs = Segment::parse_line(&line, outputs)?;
+        Ok(Line { input_patterns, outputs })
     }).collect()
 }
 
-pub fn part1() {
-    let counted: usize = read().iter().map(|line|
-        line.outputs.iter().filter(|w| match w.wires.len() {
-            2 | 4 | 3 | 7 => true,
-            _ => false
-        }).count()
-    ).sum();
-    println!("result={}", counted);
-}
+pub struct Day8;
+impl Solution for Day8 {
+    type Parsed = Vec<Line>;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError> { read(input.lines()) }
 
-pub fn part2() {
-    let lines = read();
-    let result: usize = lines.iter().map(|line| {
-        // println!("line: {:?}", line);
+    fn part1(&self, lines: &Self::Parsed) -> Answer {
+        let counted: usize = lines.iter().map(|line|
+            line.outputs.iter().filter(|w| matches!(w.wires.len(), 2 | 4 | 3 | 7)).count()
+        ).sum();
+        counted.into()
+    }
+
+    fn part2(&self, lines: &Self::Parsed) -> Answer {
+        let result: usize = lines.iter().map(|line| {
+            trace!(?line);
 
-        let decode_map = line.decode();
-        // println!("map: {:?}", decode_map);
+            let decode_map = line.decode();
+            trace!(?decode_map);
 
-        let decoded = decode_map.decode_iter(line.outputs.iter());
-        // println!("decoded: {:?}={}", decoded, decoded.to_usize());
-        decoded.to_usize()
-    }).sum();
-    println!("result={}", result);
-}
\ No newline at end of file
+            let decoded = decode_map.decode_iter(line.outputs.iter());
+            trace!(?decoded, value = decoded.to_usize());
+            decoded.to_usize()
+        }).sum();
+        result.into()
+    }
+}
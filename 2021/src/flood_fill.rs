@@ -0,0 +1,157 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::geometry::Vec2;
+use crate::grid::Grid;
+
+/// Which cells count as next to each other.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Connectivity {
+    /// Up, down, left and right.
+    Four,
+    /// [Connectivity::Four] and the diagonals.
+    Eight
+}
+impl Connectivity {
+    pub fn diagonals(&self) -> bool { *self == Connectivity::Eight }
+}
+
+/// Every coordinate reachable from `start`, including it, by stepping to neighbours for which
+/// `can_step(from, to)` holds.
+pub fn flood_fill_with<G : Grid>(
+    grid: &G, start: Vec2, connectivity: Connectivity, can_step: impl Fn(&G::Cell, &G::Cell) -> bool
+) -> HashSet<Vec2> {
+    let mut filled = HashSet::<Vec2>::new();
+    if grid.get(&start).is_none() { return filled }
+
+    let mut queue = VecDeque::from([start]);
+    filled.insert(start);
+    while let Some(c) = queue.pop_front() {
+        let from = grid.get(&c).unwrap();
+        for next in grid.neighbors(&c, connectivity.diagonals()) {
+            if filled.contains(&next) { continue }
+            if let Some(to) = grid.get(&next) {
+                if can_step(from, to) {
+                    filled.insert(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+    filled
+}
+
+/// Every passable coordinate reachable from `start`, empty if `start` is not passable itself.
+pub fn flood_fill<G : Grid>(
+    grid: &G, start: Vec2, connectivity: Connectivity, passable: impl Fn(&G::Cell) -> bool
+) -> HashSet<Vec2> {
+    if !grid.get(&start).is_some_and(&passable) { return HashSet::new() }
+    flood_fill_with(grid, start, connectivity, |_, to| passable(to))
+}
+
+/// Connected regions of a grid, see [connected_components].
+#[derive(Debug, Clone, Default)]
+pub struct Components {
+    /// Component id of every passable coordinate.
+    pub ids: HashMap<Vec2, usize>,
+    /// Members of every component, indexed by the id.
+    pub members: Vec<HashSet<Vec2>>
+}
+impl Components {
+    pub fn len(&self) -> usize { self.members.len() }
+
+    pub fn is_empty(&self) -> bool { self.members.is_empty() }
+
+    pub fn id(&self, c: &Vec2) -> Option<usize> { self.ids.get(c).copied() }
+
+    /// Size of every component, indexed by the id.
+    pub fn sizes(&self) -> Vec<usize> { self.members.iter().map(|members| members.len()).collect() }
+}
+
+/// Splits the passable cells into components of cells connected by steps for which
+/// `can_step(from, to)` holds, which should be symmetric. Ids are given out in row order of the
+/// first cell of each component.
+pub fn connected_components_with<G : Grid>(
+    grid: &G, connectivity: Connectivity,
+    passable: impl Fn(&G::Cell) -> bool, can_step: impl Fn(&G::Cell, &G::Cell) -> bool
+) -> Components {
+    let mut starts = grid.points().filter(|(_, cell)| passable(cell)).map(|(c, _)| c).collect::<Vec<_>>();
+    starts.sort_by_key(|c| c.as_tuple());
+
+    let mut components = Components::default();
+    for start in starts {
+        if components.ids.contains_key(&start) { continue }
+
+        let id = components.members.len();
+        let members =
+            flood_fill_with(grid, start, connectivity, |from, to| passable(to) && can_step(from, to));
+        for c in &members {
+            components.ids.insert(*c, id);
+        }
+        components.members.push(members);
+    }
+    components
+}
+
+/// Splits the passable cells into components of neighbouring passable cells.
+pub fn connected_components<G : Grid>(
+    grid: &G, connectivity: Connectivity, passable: impl Fn(&G::Cell) -> bool
+) -> Components {
+    connected_components_with(grid, connectivity, passable, |_, _| true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::GridMap;
+    use super::*;
+
+    fn grid(rows: &[&str]) -> GridMap<char> {
+        GridMap::new_with_data(rows.iter().map(|row| row.chars().collect()).collect())
+    }
+
+    #[test]
+    fn diagonals_only_connect_with_eight() {
+        let grid = grid(&["#..", ".#.", "..#"]);
+        let wall = |c: &char| *c == '#';
+        assert_eq!(flood_fill(&grid, Vec2::new(0, 0), Connectivity::Four, wall), HashSet::from([Vec2::new(0, 0)]));
+        assert_eq!(flood_fill(&grid, Vec2::new(0, 0), Connectivity::Eight, wall).len(), 3);
+
+        assert_eq!(connected_components(&grid, Connectivity::Four, wall).sizes(), [1, 1, 1]);
+        assert_eq!(connected_components(&grid, Connectivity::Eight, wall).sizes(), [3]);
+        // The open cells on both sides of the diagonal touch through it with 8-connectivity.
+        assert_eq!(connected_components(&grid, Connectivity::Four, |c| *c == '.').sizes(), [3, 3]);
+        assert_eq!(connected_components(&grid, Connectivity::Eight, |c| *c == '.').sizes(), [6]);
+    }
+
+    #[test]
+    fn basins() {
+        let heights = grid(&["2199943210", "3987894921", "9856789892", "8767896789", "9899965678"]);
+        let components = connected_components(&heights, Connectivity::Four, |height| *height != '9');
+        assert_eq!(components.sizes(), [3, 9, 14, 9]);
+        assert_eq!(components.id(&Vec2::new(0, 0)), Some(0));
+        assert_eq!(components.id(&Vec2::new(0, 9)), Some(1));
+        assert_eq!(components.id(&Vec2::new(2, 2)), Some(2));
+        assert_eq!(components.id(&Vec2::new(4, 9)), Some(3));
+        assert_eq!(components.id(&Vec2::new(0, 2)), None);
+        for (id, members) in components.members.iter().enumerate() {
+            assert!(members.iter().all(|c| components.id(c) == Some(id)));
+        }
+    }
+
+    #[test]
+    fn impassable_start() {
+        let grid = grid(&["..", "#."]);
+        assert!(flood_fill(&grid, Vec2::new(1, 0), Connectivity::Four, |c| *c == '.').is_empty());
+        assert!(flood_fill(&grid, Vec2::new(5, 5), Connectivity::Four, |c| *c == '.').is_empty());
+        assert!(flood_fill_with(&grid, Vec2::new(5, 5), Connectivity::Four, |_, _| true).is_empty());
+        // `flood_fill_with` only checks the steps, so it starts anywhere within the grid.
+        assert_eq!(flood_fill_with(&grid, Vec2::new(1, 0), Connectivity::Four, |_, to| *to == '.').len(), 4);
+    }
+
+    #[test]
+    fn steps_that_depend_on_both_cells() {
+        // Steps only go to cells one higher, like hiking uphill.
+        let heights = GridMap::new_with_data(vec![vec![0, 1, 2], vec![5, 4, 3], vec![6, 0, 0]]);
+        let uphill = |from: &u32, to: &u32| *to == from + 1;
+        assert_eq!(flood_fill_with(&heights, Vec2::new(0, 0), Connectivity::Four, uphill).len(), 7);
+        assert_eq!(flood_fill_with(&heights, Vec2::new(1, 1), Connectivity::Four, uphill).len(), 3);
+    }
+}
@@ -0,0 +1,878 @@
+use core::fmt::{Display, Formatter};
+use std::collections::HashSet;
+use std::num::TryFromIntError;
+use std::ops::{Add, AddAssign, Mul, Neg, RangeInclusive, Sub, SubAssign};
+use itertools::Either;
+use crate::grid::Offset;
+use crate::math::gcd;
+
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct Vec2 {
+    /// aka row
+    pub x: usize,
+    /// aka col
+    pub y: usize
+}
+
+impl Vec2 {
+    pub fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    pub fn as_tuple(&self) -> (usize, usize) { (self.x, self.y) }
+
+    /// `self + delta`, `None` if that leaves the unsigned range.
+    pub fn checked_add_signed(&self, delta: Vec2Signed) -> Option<Self> {
+        Some(Self::new(self.x.checked_add_signed(delta.x as isize)?, self.y.checked_add_signed(delta.y as isize)?))
+    }
+
+    /// The neighbour in `direction`, `None` if that would be a negative coordinate.
+    pub fn step(&self, direction: impl Into<Vec2Signed>) -> Option<Self> {
+        self.checked_add_signed(direction.into())
+    }
+
+    /// The 4 orthogonal neighbours, and the 4 diagonal ones with `diagonals`, that are not negative.
+    pub fn adjacent(&self, diagonals: bool) -> impl Iterator<Item = Vec2> {
+        let c = *self;
+        Direction8::ALL.into_iter().filter(move |d| diagonals || !d.is_diagonal()).filter_map(move |d| c.step(d))
+    }
+
+    pub fn manhattan(&self, other: &Vec2) -> usize { self.x.abs_diff(other.x) + self.y.abs_diff(other.y) }
+
+    pub fn chebyshev(&self, other: &Vec2) -> usize { self.x.abs_diff(other.x).max(self.y.abs_diff(other.y)) }
+
+    pub fn euclidean(&self, other: &Vec2) -> f64 {
+        (self.x.abs_diff(other.x) as f64).hypot(self.y.abs_diff(other.y) as f64)
+    }
+}
+impl Display for Vec2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_fmt(format_args!("({},{})", self.x, self.y))
+    }
+}
+impl From<Vec2> for (usize, usize) {
+    fn from(v: Vec2) -> Self { v.as_tuple() }
+}
+impl TryFrom<Vec2Signed> for Vec2 {
+    type Error = TryFromIntError;
+
+    fn try_from(v: Vec2Signed) -> Result<Self, Self::Error> { Ok(Vec2::new(v.x.try_into()?, v.y.try_into()?)) }
+}
+impl Add for Vec2 {
+    type Output = Vec2;
+    fn add(self, rhs: Self) -> Self::Output { Vec2::new(self.x + rhs.x, self.y + rhs.y) }
+}
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, rhs: Self) { *self = *self + rhs }
+}
+/// Panics on underflow, like `usize` does.
+impl Sub for Vec2 {
+    type Output = Vec2;
+    fn sub(self, rhs: Self) -> Self::Output { Vec2::new(self.x - rhs.x, self.y - rhs.y) }
+}
+impl SubAssign for Vec2 {
+    fn sub_assign(&mut self, rhs: Self) { *self = *self - rhs }
+}
+impl Mul<usize> for Vec2 {
+    type Output = Vec2;
+    fn mul(self, rhs: usize) -> Self::Output { Vec2::new(self.x * rhs, self.y * rhs) }
+}
+
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct Vec2Signed {
+    /// aka row
+    pub x: i64,
+    /// aka col
+    pub y: i64
+}
+impl Vec2Signed {
+    pub fn inverse_x(&self) -> Self {
+        Self { x: -self.x, y: self.y }
+    }
+
+    pub fn inverse_y(&self) -> Self {
+        Self { x: self.x, y: -self.y }
+    }
+
+    /// Moves `y` one step closer to 0, leaving it as is if it already is 0.
+    pub fn y_towards_0(&mut self) {
+        self.y -= self.y.signum();
+    }
+
+    /// Both components reduced to -1, 0 or 1.
+    pub fn signum(&self) -> Self { Self::new(self.x.signum(), self.y.signum()) }
+
+    pub fn manhattan(&self, other: &Vec2Signed) -> u64 { self.x.abs_diff(other.x) + self.y.abs_diff(other.y) }
+
+    pub fn chebyshev(&self, other: &Vec2Signed) -> u64 { self.x.abs_diff(other.x).max(self.y.abs_diff(other.y)) }
+
+    pub fn euclidean(&self, other: &Vec2Signed) -> f64 {
+        (self.x.abs_diff(other.x) as f64).hypot(self.y.abs_diff(other.y) as f64)
+    }
+}
+impl Vec2Signed {
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    pub fn to_vec2(&self, offset: &Offset) -> Vec2 {
+        offset.map(self.x, self.y)
+    }
+}
+impl Display for Vec2Signed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_fmt(format_args!("({},{})", self.x, self.y))
+    }
+}
+impl TryFrom<Vec2> for Vec2Signed {
+    type Error = TryFromIntError;
+
+    fn try_from(v: Vec2) -> Result<Self, Self::Error> { Ok(Vec2Signed::new(v.x.try_into()?, v.y.try_into()?)) }
+}
+impl Add for Vec2Signed {
+    type Output = Vec2Signed;
+    fn add(self, rhs: Self) -> Self::Output { Vec2Signed::new(self.x + rhs.x, self.y + rhs.y) }
+}
+impl AddAssign for Vec2Signed {
+    fn add_assign(&mut self, rhs: Self) { *self = *self + rhs }
+}
+impl Sub for Vec2Signed {
+    type Output = Vec2Signed;
+    fn sub(self, rhs: Self) -> Self::Output { Vec2Signed::new(self.x - rhs.x, self.y - rhs.y) }
+}
+impl SubAssign for Vec2Signed {
+    fn sub_assign(&mut self, rhs: Self) { *self = *self - rhs }
+}
+impl Mul<i64> for Vec2Signed {
+    type Output = Vec2Signed;
+    fn mul(self, rhs: i64) -> Self::Output { Vec2Signed::new(self.x * rhs, self.y * rhs) }
+}
+impl Neg for Vec2Signed {
+    type Output = Vec2Signed;
+    fn neg(self) -> Self::Output { Vec2Signed::new(-self.x, -self.y) }
+}
+
+/// An axis aligned rectangle of [Vec2Signed] coordinates, `min` and `max` both included, so it is
+/// never empty.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct Rect {
+    pub min: Vec2Signed,
+    pub max: Vec2Signed
+}
+impl Rect {
+    /// The rectangle spanned by two opposite corners, in any order.
+    pub fn new(a: Vec2Signed, b: Vec2Signed) -> Self {
+        Rect { min: Vec2Signed::new(a.x.min(b.x), a.y.min(b.y)), max: Vec2Signed::new(a.x.max(b.x), a.y.max(b.y)) }
+    }
+
+    /// The rectangle from `min` up to but excluding `end`, `None` if that is empty.
+    pub fn from_exclusive(min: Vec2Signed, end: Vec2Signed) -> Option<Self> {
+        if end.x > min.x && end.y > min.y { Some(Rect { min, max: end - Vec2Signed::new(1, 1) }) } else { None }
+    }
+
+    /// The rectangle holding just `c`.
+    pub fn point(c: Vec2Signed) -> Self { Rect { min: c, max: c } }
+
+    /// The first coordinate past `max` on both axes.
+    pub fn end(&self) -> Vec2Signed { self.max + Vec2Signed::new(1, 1) }
+
+    /// Number of rows.
+    pub fn height(&self) -> u64 { self.max.x.abs_diff(self.min.x) + 1 }
+
+    /// Number of columns.
+    pub fn width(&self) -> u64 { self.max.y.abs_diff(self.min.y) + 1 }
+
+    pub fn area(&self) -> u64 { self.height() * self.width() }
+
+    pub fn contains(&self, c: &Vec2Signed) -> bool {
+        (self.min.x..=self.max.x).contains(&c.x) && (self.min.y..=self.max.y).contains(&c.y)
+    }
+
+    pub fn contains_rect(&self, other: &Rect) -> bool { self.contains(&other.min) && self.contains(&other.max) }
+
+    /// The overlap of both, `None` if they do not overlap.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let min = Vec2Signed::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y));
+        let max = Vec2Signed::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y));
+        if min.x <= max.x && min.y <= max.y { Some(Rect { min, max }) } else { None }
+    }
+
+    /// The smallest rectangle containing both.
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            min: Vec2Signed::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Vec2Signed::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y))
+        }
+    }
+
+    /// The smallest rectangle containing this one and `c`.
+    pub fn include(&self, c: &Vec2Signed) -> Rect { self.union(&Rect::point(*c)) }
+
+    /// Whether `c` lies beyond the edges faced by `direction`, so that moving further that way never
+    /// enters the rectangle. Diagonal directions test both of their edges.
+    pub fn is_past(&self, c: &Vec2Signed, direction: impl Into<Vec2Signed>) -> bool {
+        let d = direction.into();
+        (d.x > 0 && c.x > self.max.x) || (d.x < 0 && c.x < self.min.x) ||
+            (d.y > 0 && c.y > self.max.y) || (d.y < 0 && c.y < self.min.y)
+    }
+
+    pub fn rows(&self) -> RangeInclusive<i64> { self.min.x..=self.max.x }
+
+    pub fn cols(&self) -> RangeInclusive<i64> { self.min.y..=self.max.y }
+
+    /// Every coordinate, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Vec2Signed> {
+        let cols = self.cols();
+        self.rows().flat_map(move |x| cols.clone().map(move |y| Vec2Signed::new(x, y)))
+    }
+}
+impl Display for Rect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_fmt(format_args!("{}..={}", self.min, self.max))
+    }
+}
+
+/// How a segment between integer endpoints is turned into grid cells.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Raster {
+    /// Only the points lying exactly on the segment, see [LatticePoints].
+    Exact,
+    /// The cells closest to the segment, see [Bresenham].
+    Bresenham
+}
+impl Raster {
+    /// The cells from `from` to `to`, both included.
+    pub fn points(&self, from: Vec2Signed, to: Vec2Signed) -> impl Iterator<Item = Vec2Signed> {
+        match self {
+            Raster::Exact => Either::Left(LatticePoints::new(from, to)),
+            Raster::Bresenham => Either::Right(Bresenham::new(from, to))
+        }
+    }
+}
+
+/// The integer points on a segment, from `from` to `to`. Steps by the direction divided by the gcd of
+/// its components, so a segment with a slope of 2/3 only passes through every third column.
+#[derive(Debug, Clone)]
+pub struct LatticePoints {
+    next: Vec2Signed,
+    step: Vec2Signed,
+    remaining: u64
+}
+impl LatticePoints {
+    pub fn new(from: Vec2Signed, to: Vec2Signed) -> Self {
+        let d = to - from;
+        let steps = gcd(d.x.unsigned_abs(), d.y.unsigned_abs());
+        let step =
+            if steps == 0 { Vec2Signed::new(0, 0) } else { Vec2Signed::new(d.x / steps as i64, d.y / steps as i64) };
+        LatticePoints { next: from, step, remaining: steps + 1 }
+    }
+}
+impl Iterator for LatticePoints {
+    type Item = Vec2Signed;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 { return None }
+        let current = self.next;
+        self.next += self.step;
+        self.remaining -= 1;
+        Some(current)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.remaining as usize, Some(self.remaining as usize)) }
+}
+
+/// Bresenham's approximation of a segment of any slope: one cell per step along the longer axis, or
+/// per diagonal step, from `from` to `to`.
+#[derive(Debug, Clone)]
+pub struct Bresenham {
+    next: Option<Vec2Signed>,
+    to: Vec2Signed,
+    delta: Vec2Signed,
+    sign: Vec2Signed,
+    error: i64
+}
+impl Bresenham {
+    pub fn new(from: Vec2Signed, to: Vec2Signed) -> Self {
+        let delta = Vec2Signed::new((to.x - from.x).abs(), -(to.y - from.y).abs());
+        Bresenham { next: Some(from), to, delta, sign: (to - from).signum(), error: delta.x + delta.y }
+    }
+}
+impl Iterator for Bresenham {
+    type Item = Vec2Signed;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        if current == self.to {
+            self.next = None;
+            return Some(current)
+        }
+
+        let mut next = current;
+        let doubled = 2 * self.error;
+        if doubled >= self.delta.y {
+            self.error += self.delta.y;
+            next.x += self.sign.x;
+        }
+        if doubled <= self.delta.x {
+            self.error += self.delta.x;
+            next.y += self.sign.y;
+        }
+        self.next = Some(next);
+        Some(current)
+    }
+}
+
+/// One of the 4 orthogonal directions on a grid where rows grow downwards.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub enum Direction { Up, Right, Down, Left }
+impl Direction {
+    /// Clockwise, starting from [Direction::Up].
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    fn from_index(idx: usize) -> Self { Self::ALL[idx % Self::ALL.len()] }
+
+    fn index(&self) -> usize { *self as usize }
+
+    pub fn turn_right(&self) -> Self { Self::from_index(self.index() + 1) }
+
+    pub fn turn_left(&self) -> Self { Self::from_index(self.index() + 3) }
+
+    pub fn opposite(&self) -> Self { Self::from_index(self.index() + 2) }
+
+    /// Change in `(row, col)` of one step.
+    pub fn delta(&self) -> Vec2Signed { Direction8::from(*self).delta() }
+}
+impl From<Direction> for Vec2Signed {
+    fn from(d: Direction) -> Self { d.delta() }
+}
+
+/// [Direction] and the 4 diagonals in between.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub enum Direction8 { Up, UpRight, Right, DownRight, Down, DownLeft, Left, UpLeft }
+impl Direction8 {
+    /// Clockwise, starting from [Direction8::Up].
+    pub const ALL: [Direction8; 8] = [
+        Direction8::Up, Direction8::UpRight, Direction8::Right, Direction8::DownRight,
+        Direction8::Down, Direction8::DownLeft, Direction8::Left, Direction8::UpLeft
+    ];
+
+    fn from_index(idx: usize) -> Self { Self::ALL[idx % Self::ALL.len()] }
+
+    fn index(&self) -> usize { *self as usize }
+
+    /// Turns 45 degrees clockwise.
+    pub fn turn_right(&self) -> Self { Self::from_index(self.index() + 1) }
+
+    /// Turns 45 degrees counterclockwise.
+    pub fn turn_left(&self) -> Self { Self::from_index(self.index() + 7) }
+
+    pub fn opposite(&self) -> Self { Self::from_index(self.index() + 4) }
+
+    pub fn is_diagonal(&self) -> bool { self.index() % 2 == 1 }
+
+    /// Change in `(row, col)` of one step.
+    pub fn delta(&self) -> Vec2Signed {
+        match self {
+            Direction8::Up => Vec2Signed::new(-1, 0),
+            Direction8::UpRight => Vec2Signed::new(-1, 1),
+            Direction8::Right => Vec2Signed::new(0, 1),
+            Direction8::DownRight => Vec2Signed::new(1, 1),
+            Direction8::Down => Vec2Signed::new(1, 0),
+            Direction8::DownLeft => Vec2Signed::new(1, -1),
+            Direction8::Left => Vec2Signed::new(0, -1),
+            Direction8::UpLeft => Vec2Signed::new(-1, -1)
+        }
+    }
+}
+impl From<Direction> for Direction8 {
+    fn from(d: Direction) -> Self { Self::from_index(d.index() * 2) }
+}
+impl From<Direction8> for Vec2Signed {
+    fn from(d: Direction8) -> Self { d.delta() }
+}
+
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone, Default)]
+pub struct Vec3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64
+}
+impl Vec3 {
+    /// Unit steps to the 6 face neighbours.
+    pub const FACES: [Vec3; 6] = [
+        Vec3::new(1, 0, 0), Vec3::new(-1, 0, 0), Vec3::new(0, 1, 0),
+        Vec3::new(0, -1, 0), Vec3::new(0, 0, 1), Vec3::new(0, 0, -1)
+    ];
+
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn as_array(&self) -> [i64; 3] { [self.x, self.y, self.z] }
+
+    pub fn from_array([x, y, z]: [i64; 3]) -> Self { Self::new(x, y, z) }
+
+    /// The 6 coordinates sharing a face with this one.
+    pub fn neighbors(&self) -> impl Iterator<Item = Vec3> + '_ { Vec3::FACES.iter().map(move |d| *self + *d) }
+
+    pub fn manhattan(&self, other: &Vec3) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    pub fn chebyshev(&self, other: &Vec3) -> u64 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y)).max(self.z.abs_diff(other.z))
+    }
+
+    pub fn euclidean(&self, other: &Vec3) -> f64 {
+        let d = *self - *other;
+        ((d.x * d.x + d.y * d.y + d.z * d.z) as f64).sqrt()
+    }
+}
+impl Display for Vec3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_fmt(format_args!("({},{},{})", self.x, self.y, self.z))
+    }
+}
+impl Add for Vec3 {
+    type Output = Vec3;
+    fn add(self, rhs: Self) -> Self::Output { Vec3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z) }
+}
+impl AddAssign for Vec3 {
+    fn add_assign(&mut self, rhs: Self) { *self = *self + rhs }
+}
+impl Sub for Vec3 {
+    type Output = Vec3;
+    fn sub(self, rhs: Self) -> Self::Output { Vec3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z) }
+}
+impl SubAssign for Vec3 {
+    fn sub_assign(&mut self, rhs: Self) { *self = *self - rhs }
+}
+impl Mul<i64> for Vec3 {
+    type Output = Vec3;
+    fn mul(self, rhs: i64) -> Self::Output { Vec3::new(self.x * rhs, self.y * rhs, self.z * rhs) }
+}
+impl Neg for Vec3 {
+    type Output = Vec3;
+    fn neg(self) -> Self::Output { Vec3::new(-self.x, -self.y, -self.z) }
+}
+
+/// One of the 24 rotations that map the axes onto axes, as in turning a cube in place.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct Rotation {
+    /// Component `i` of the result is `signs[i]` times component `axes[i]` of the input.
+    axes: [usize; 3],
+    signs: [i64; 3]
+}
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation { axes: [0, 1, 2], signs: [1, 1, 1] };
+
+    /// All 24 rotations, starting with [Rotation::IDENTITY].
+    pub fn all() -> Vec<Rotation> {
+        let permutations = [[0, 1, 2], [1, 2, 0], [2, 0, 1], [0, 2, 1], [2, 1, 0], [1, 0, 2]];
+        let mut rotations = Vec::with_capacity(24);
+        for (idx, axes) in permutations.into_iter().enumerate() {
+            // The first 3 permutations are even, the rest swap 2 axes and need an odd number of flips.
+            let parity = if idx < 3 { 1 } else { -1 };
+            for signs in [[1, 1, 1], [1, -1, -1], [-1, 1, -1], [-1, -1, 1]] {
+                rotations.push(Rotation { axes, signs: signs.map(|sign| sign * parity) });
+            }
+        }
+        rotations
+    }
+
+    pub fn apply(&self, v: Vec3) -> Vec3 {
+        let v = v.as_array();
+        Vec3::from_array([0, 1, 2].map(|i| self.signs[i] * v[self.axes[i]]))
+    }
+
+    /// The rotation undoing this one.
+    pub fn inverse(&self) -> Rotation {
+        let mut inverse = Rotation::IDENTITY;
+        for i in 0..3 {
+            inverse.axes[self.axes[i]] = i;
+            inverse.signs[self.axes[i]] = self.signs[i];
+        }
+        inverse
+    }
+
+    /// Applies this rotation and then `next`.
+    pub fn then(&self, next: &Rotation) -> Rotation {
+        Rotation {
+            axes: [0, 1, 2].map(|i| self.axes[next.axes[i]]),
+            signs: [0, 1, 2].map(|i| next.signs[i] * self.signs[next.axes[i]])
+        }
+    }
+}
+
+/// An axis aligned box of [Vec3] coordinates, `min` and `max` both included, like [Rect].
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct Cuboid {
+    pub min: Vec3,
+    pub max: Vec3
+}
+impl Cuboid {
+    /// The cuboid spanned by two opposite corners, in any order.
+    pub fn new(a: Vec3, b: Vec3) -> Self {
+        Cuboid {
+            min: Vec3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: Vec3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z))
+        }
+    }
+
+    /// The cuboid holding just `c`.
+    pub fn point(c: Vec3) -> Self { Cuboid { min: c, max: c } }
+
+    pub fn volume(&self) -> u64 {
+        (self.max.x.abs_diff(self.min.x) + 1) * (self.max.y.abs_diff(self.min.y) + 1) *
+            (self.max.z.abs_diff(self.min.z) + 1)
+    }
+
+    pub fn contains(&self, c: &Vec3) -> bool {
+        (self.min.x..=self.max.x).contains(&c.x) && (self.min.y..=self.max.y).contains(&c.y) &&
+            (self.min.z..=self.max.z).contains(&c.z)
+    }
+
+    /// The overlap of both, `None` if they do not overlap.
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        let min = Vec3::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y), self.min.z.max(other.min.z));
+        let max = Vec3::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y), self.max.z.min(other.max.z));
+        if min.x <= max.x && min.y <= max.y && min.z <= max.z { Some(Cuboid { min, max }) } else { None }
+    }
+
+    /// The smallest cuboid containing both.
+    pub fn union(&self, other: &Cuboid) -> Cuboid {
+        Cuboid {
+            min: Vec3::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y), self.min.z.min(other.min.z)),
+            max: Vec3::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y), self.max.z.max(other.max.z))
+        }
+    }
+
+    /// This cuboid without `other`, as at most 6 disjoint cuboids.
+    pub fn subtract(&self, other: &Cuboid) -> Vec<Cuboid> {
+        let Some(cut) = self.intersection(other) else { return vec![*self] };
+
+        let mut pieces = Vec::with_capacity(6);
+        let mut rest = *self;
+        // Slice off whatever is outside of `cut` along one axis at a time, narrowing `rest` to it.
+        for axis in 0..3 {
+            let (min, max) = (rest.min.as_array(), rest.max.as_array());
+            let (cut_min, cut_max) = (cut.min.as_array(), cut.max.as_array());
+            if min[axis] < cut_min[axis] {
+                let mut below = max;
+                below[axis] = cut_min[axis] - 1;
+                pieces.push(Cuboid { min: rest.min, max: Vec3::from_array(below) });
+            }
+            if max[axis] > cut_max[axis] {
+                let mut above = min;
+                above[axis] = cut_max[axis] + 1;
+                pieces.push(Cuboid { min: Vec3::from_array(above), max: rest.max });
+            }
+            let (mut min, mut max) = (min, max);
+            min[axis] = cut_min[axis];
+            max[axis] = cut_max[axis];
+            rest = Cuboid { min: Vec3::from_array(min), max: Vec3::from_array(max) };
+        }
+        pieces
+    }
+
+    /// Every coordinate, `x` changing slowest.
+    pub fn points(&self) -> impl Iterator<Item = Vec3> {
+        let Cuboid { min, max } = *self;
+        (min.x..=max.x).flat_map(move |x|
+            (min.y..=max.y).flat_map(move |y| (min.z..=max.z).map(move |z| Vec3::new(x, y, z)))
+        )
+    }
+}
+impl Display for Cuboid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_fmt(format_args!("{}..={}", self.min, self.max))
+    }
+}
+
+/// A sparse set of filled [Vec3] cells.
+#[derive(Debug, Clone, Default)]
+pub struct Voxels {
+    cells: HashSet<Vec3>
+}
+impl Voxels {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn len(&self) -> usize { self.cells.len() }
+
+    pub fn is_empty(&self) -> bool { self.cells.is_empty() }
+
+    pub fn contains(&self, c: &Vec3) -> bool { self.cells.contains(c) }
+
+    /// `false` if `c` was already filled.
+    pub fn insert(&mut self, c: Vec3) -> bool { self.cells.insert(c) }
+
+    /// `false` if `c` was not filled.
+    pub fn remove(&mut self, c: &Vec3) -> bool { self.cells.remove(c) }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Vec3> { self.cells.iter() }
+
+    /// Filled cells sharing a face with `c`.
+    pub fn neighbors<'a>(&'a self, c: &'a Vec3) -> impl Iterator<Item = Vec3> + 'a {
+        c.neighbors().filter(|n| self.contains(n))
+    }
+
+    /// Number of faces of filled cells that do not touch another filled cell.
+    pub fn surface_area(&self) -> usize {
+        self.cells.iter().map(|c| 6 - self.neighbors(c).count()).sum()
+    }
+
+    /// The smallest cuboid holding every filled cell, `None` if there are none.
+    pub fn bounds(&self) -> Option<Cuboid> {
+        self.cells.iter().map(|c| Cuboid::point(*c)).reduce(|a, b| a.union(&b))
+    }
+}
+impl FromIterator<Vec3> for Voxels {
+    fn from_iter<T : IntoIterator<Item = Vec3>>(iter: T) -> Self { Voxels { cells: iter.into_iter().collect() } }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use super::*;
+
+    fn segments() -> Vec<(Vec2Signed, Vec2Signed)> {
+        let ends = [(0, 0), (6, 9), (-3, 7), (5, -2), (0, 4), (-4, 0), (-7, -7), (2, 1)];
+        ends.iter().cartesian_product(ends.iter())
+            .map(|((x1, y1), (x2, y2))| (Vec2Signed::new(*x1, *y1), Vec2Signed::new(*x2, *y2)))
+            .collect()
+    }
+
+    #[test]
+    fn lattice_points_step_by_gcd() {
+        let points = LatticePoints::new(Vec2Signed::new(0, 0), Vec2Signed::new(6, 9)).collect_vec();
+        assert_eq!(points, [(0, 0), (2, 3), (4, 6), (6, 9)].map(|(x, y)| Vec2Signed::new(x, y)));
+
+        for (from, to) in segments() {
+            let d = to - from;
+            let points = LatticePoints::new(from, to).collect_vec();
+            assert_eq!(points.len() as u64, gcd(d.x.unsigned_abs(), d.y.unsigned_abs()) + 1, "{} to {}", from, to);
+            assert_eq!((points[0], *points.last().unwrap()), (from, to));
+        }
+    }
+
+    #[test]
+    fn bresenham_steps_to_adjacent_cells() {
+        for (from, to) in segments() {
+            let points = Bresenham::new(from, to).collect_vec();
+            assert_eq!(points.len() as u64, from.chebyshev(&to) + 1, "{} to {}", from, to);
+            assert_eq!((points[0], *points.last().unwrap()), (from, to));
+            for (a, b) in points.iter().tuple_windows() {
+                assert_eq!(a.chebyshev(b), 1, "{} to {} steps from {} to {}", from, to, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn zero_length_segment() {
+        let c = Vec2Signed::new(-3, 7);
+        for raster in [Raster::Exact, Raster::Bresenham] {
+            assert_eq!(raster.points(c, c).collect_vec(), [c]);
+        }
+    }
+
+    #[test]
+    fn vec2_conversions_round_trip() {
+        for (x, y) in [(0, 0), (3, 7), (12, 0)] {
+            let v = Vec2::new(x, y);
+            let signed = Vec2Signed::try_from(v).unwrap();
+            assert_eq!(signed, Vec2Signed::new(x as i64, y as i64));
+            assert_eq!(Vec2::try_from(signed), Ok(v));
+        }
+        assert!(Vec2::try_from(Vec2Signed::new(-1, 4)).is_err());
+        assert!(Vec2::try_from(Vec2Signed::new(4, -1)).is_err());
+        assert!(Vec2Signed::try_from(Vec2::new(usize::MAX, 0)).is_err());
+    }
+
+    #[test]
+    fn distances_with_negative_components() {
+        let a = Vec2Signed::new(-3, 4);
+        let b = Vec2Signed::new(2, -8);
+        assert_eq!((a.manhattan(&b), b.manhattan(&a)), (17, 17));
+        assert_eq!((a.chebyshev(&b), b.chebyshev(&a)), (12, 12));
+        assert_eq!(a.euclidean(&b), 13.0);
+        assert_eq!(Vec2Signed::new(-5, -5).manhattan(&Vec2Signed::new(-5, -5)), 0);
+        assert_eq!(Vec2Signed::new(i64::MIN, 0).manhattan(&Vec2Signed::new(i64::MAX, 0)), u64::MAX);
+
+        assert_eq!(Vec2::new(1, 7).manhattan(&Vec2::new(4, 3)), 7);
+        assert_eq!(Vec2::new(1, 7).chebyshev(&Vec2::new(4, 3)), 4);
+    }
+
+    #[test]
+    fn directions_turn_and_convert() {
+        for d in Direction::ALL {
+            assert_eq!(d.turn_right().turn_left(), d);
+            assert_eq!(d.turn_right().turn_right(), d.opposite());
+            assert_eq!(d.opposite().delta(), -d.delta());
+            assert_eq!(Vec2Signed::from(Direction8::from(d)), d.delta());
+            assert!(!Direction8::from(d).is_diagonal());
+        }
+        let deltas = [(-1, 0), (0, 1), (1, 0), (0, -1)].map(|(x, y)| Vec2Signed::new(x, y));
+        assert_eq!(Direction::ALL.map(|d| d.delta()), deltas);
+
+        for d in Direction8::ALL {
+            assert_eq!(d.turn_right().turn_left(), d);
+            assert_eq!(d.opposite().delta(), -d.delta());
+            assert_eq!(d.delta().x.abs() + d.delta().y.abs() == 2, d.is_diagonal(), "{:?}", d);
+        }
+        assert_eq!(Direction8::Left.turn_right(), Direction8::UpLeft);
+        assert_eq!(Direction8::UpLeft.turn_right(), Direction8::Up);
+
+        let origin = Vec2::new(0, 0);
+        assert_eq!(origin.step(Direction::Up), None);
+        assert_eq!(origin.step(Direction8::DownRight), Some(Vec2::new(1, 1)));
+        assert_eq!(origin.adjacent(true).collect_vec(), [Vec2::new(0, 1), Vec2::new(1, 1), Vec2::new(1, 0)]);
+        assert_eq!(Vec2::new(1, 1).adjacent(false).count(), 4);
+    }
+
+    fn rect() -> Rect { Rect::new(Vec2Signed::new(4, -1), Vec2Signed::new(-2, 3)) }
+
+    #[test]
+    fn rect_contains_its_edges_only() {
+        let r = rect();
+        assert_eq!((r.min, r.max), (Vec2Signed::new(-2, -1), Vec2Signed::new(4, 3)));
+        assert_eq!((r.height(), r.width(), r.area()), (7, 5, 35));
+        assert_eq!(r.points().count(), 35);
+        assert!(r.points().all(|c| r.contains(&c)));
+
+        for row in r.rows() {
+            assert!(r.contains(&Vec2Signed::new(row, -1)) && r.contains(&Vec2Signed::new(row, 3)), "row {}", row);
+            assert!(!r.contains(&Vec2Signed::new(row, -2)) && !r.contains(&Vec2Signed::new(row, 4)), "row {}", row);
+        }
+        for col in r.cols() {
+            assert!(r.contains(&Vec2Signed::new(-2, col)) && r.contains(&Vec2Signed::new(4, col)), "col {}", col);
+            assert!(!r.contains(&Vec2Signed::new(-3, col)) && !r.contains(&Vec2Signed::new(5, col)), "col {}", col);
+        }
+        for corner in [(-3, -2), (-3, 4), (5, -2), (5, 4)] {
+            assert!(!r.contains(&Vec2Signed::new(corner.0, corner.1)), "{:?}", corner);
+        }
+    }
+
+    #[test]
+    fn rect_exclusive_end() {
+        let r = rect();
+        assert_eq!(Rect::from_exclusive(r.min, r.end()), Some(r));
+        assert_eq!(Rect::from_exclusive(r.min, r.max), Some(Rect::new(r.min, Vec2Signed::new(3, 2))));
+        assert_eq!(Rect::from_exclusive(r.min, r.min + Vec2Signed::new(1, 1)), Some(Rect::point(r.min)));
+        assert_eq!(Rect::from_exclusive(r.min, r.min), None);
+        assert_eq!(Rect::from_exclusive(r.min, Vec2Signed::new(4, -1)), None);
+        assert_eq!(Rect::from_exclusive(r.min, Vec2Signed::new(-2, 3)), None);
+        assert!(!Rect::from_exclusive(r.min, r.end()).unwrap().contains(&r.end()));
+    }
+
+    #[test]
+    fn rect_intersection_and_union() {
+        let r = rect();
+        let touching = Rect::new(Vec2Signed::new(4, 3), Vec2Signed::new(9, 9));
+        assert_eq!(r.intersection(&touching), Some(Rect::point(r.max)));
+        assert_eq!(r.intersection(&r), Some(r));
+
+        for disjoint in [
+            Rect::new(Vec2Signed::new(5, -1), Vec2Signed::new(9, 3)),
+            Rect::new(Vec2Signed::new(-9, -1), Vec2Signed::new(-3, 3)),
+            Rect::new(Vec2Signed::new(-2, 4), Vec2Signed::new(4, 9)),
+            Rect::new(Vec2Signed::new(-2, -9), Vec2Signed::new(4, -2)),
+            Rect::new(Vec2Signed::new(5, 4), Vec2Signed::new(6, 6))
+        ] {
+            assert_eq!(r.intersection(&disjoint), None, "{:?}", disjoint);
+            assert_eq!(disjoint.intersection(&r), None, "{:?}", disjoint);
+        }
+
+        let union = r.union(&touching);
+        assert_eq!(union, Rect::new(Vec2Signed::new(-2, -1), Vec2Signed::new(9, 9)));
+        assert!(union.contains_rect(&r) && union.contains_rect(&touching));
+        assert_eq!(r.include(&Vec2Signed::new(-5, 0)), Rect::new(Vec2Signed::new(-5, -1), r.max));
+    }
+
+    #[test]
+    fn rect_is_past_every_direction() {
+        let r = rect();
+        let inside = Vec2Signed::new(0, 0);
+        for d in Direction8::ALL {
+            assert!(!r.is_past(&inside, d), "{:?}", d);
+            assert!(!r.is_past(&r.min, d) && !r.is_past(&r.max, d), "{:?}", d);
+        }
+
+        for d in Direction::ALL {
+            let beyond = inside + d.delta() * 6;
+            assert!(!r.contains(&beyond), "{:?}", d);
+            assert!(r.is_past(&beyond, d), "{:?}", d);
+            assert!(!r.is_past(&beyond, d.opposite()), "{:?}", d);
+            assert!(!r.is_past(&beyond, d.turn_right()) && !r.is_past(&beyond, d.turn_left()), "{:?}", d);
+        }
+
+        let below_right = Vec2Signed::new(5, 0);
+        assert!(r.is_past(&below_right, Direction8::DownRight) && r.is_past(&below_right, Direction8::DownLeft));
+        assert!(!r.is_past(&below_right, Direction8::UpRight) && !r.is_past(&below_right, Direction8::UpLeft));
+        let above_left = Vec2Signed::new(-3, -2);
+        for d in [Direction8::Up, Direction8::Left, Direction8::UpLeft, Direction8::UpRight, Direction8::DownLeft] {
+            assert!(r.is_past(&above_left, d), "{:?}", d);
+        }
+        assert!(!r.is_past(&above_left, Direction8::DownRight));
+    }
+
+    /// Determinant of the matrix whose columns are the images of the unit vectors.
+    fn determinant(r: &Rotation) -> i64 {
+        let [a, b, c] = [Vec3::new(1, 0, 0), Vec3::new(0, 1, 0), Vec3::new(0, 0, 1)].map(|e| r.apply(e).as_array());
+        a[0] * (b[1] * c[2] - b[2] * c[1]) - b[0] * (a[1] * c[2] - a[2] * c[1]) + c[0] * (a[1] * b[2] - a[2] * b[1])
+    }
+
+    #[test]
+    fn rotations_are_distinct() {
+        let v = Vec3::new(1, 2, 3);
+        let images = Rotation::all().iter().map(|r| r.apply(v)).collect::<HashSet<_>>();
+        assert_eq!(images.len(), 24);
+        for r in Rotation::all() {
+            assert_eq!(determinant(&r), 1, "{:?} is a reflection", r);
+        }
+        assert_eq!(determinant(&Rotation { axes: [0, 1, 2], signs: [1, 1, -1] }), -1);
+        assert_eq!(Rotation::all()[0], Rotation::IDENTITY);
+    }
+
+    #[test]
+    fn rotation_inverse_and_then() {
+        let v = Vec3::new(1, 2, 3);
+        for r in Rotation::all() {
+            assert_eq!(r.then(&r.inverse()), Rotation::IDENTITY);
+            assert_eq!(r.inverse().then(&r), Rotation::IDENTITY);
+            for next in Rotation::all() {
+                assert_eq!(r.then(&next).apply(v), next.apply(r.apply(v)), "{:?} then {:?}", r, next);
+            }
+        }
+    }
+
+    #[test]
+    fn subtract_leaves_disjoint_pieces() {
+        let a = Cuboid::new(Vec3::new(0, 0, 0), Vec3::new(4, 5, 6));
+        let others = [
+            Cuboid::new(Vec3::new(1, 1, 1), Vec3::new(2, 3, 4)),
+            Cuboid::new(Vec3::new(-2, 3, 5), Vec3::new(2, 9, 9)),
+            Cuboid::new(Vec3::new(-1, -1, -1), Vec3::new(10, 10, 10)),
+            Cuboid::new(Vec3::new(4, 5, 6), Vec3::new(4, 5, 6)),
+            Cuboid::new(Vec3::new(5, 0, 0), Vec3::new(8, 5, 6))
+        ];
+        for b in others {
+            let pieces = a.subtract(&b);
+            for (p, q) in pieces.iter().tuple_combinations() {
+                assert_eq!(p.intersection(q), None, "{} and {} overlap", p, q);
+            }
+            for p in &pieces {
+                assert_eq!(p.intersection(&b), None, "{} overlaps {}", p, b);
+                assert_eq!(p.intersection(&a), Some(*p), "{} is outside of {}", p, a);
+            }
+            let overlap = a.intersection(&b).map_or(0, |cut| cut.volume());
+            assert_eq!(pieces.iter().map(|p| p.volume()).sum::<u64>(), a.volume() - overlap, "{} - {}", a, b);
+        }
+    }
+
+    #[test]
+    fn surface_area_of_touching_cubes() {
+        let voxels = [Vec3::new(1, 1, 1), Vec3::new(2, 1, 1)].into_iter().collect::<Voxels>();
+        assert_eq!(voxels.surface_area(), 10);
+        assert_eq!(voxels.bounds(), Some(Cuboid::new(Vec3::new(1, 1, 1), Vec3::new(2, 1, 1))));
+    }
+}
@@ -0,0 +1,802 @@
+use core::fmt::{Display, Formatter};
+use std::collections::HashMap;
+use ndarray::{Array2, ArrayBase};
+use crate::geometry::{Rect, Vec2, Vec2Signed};
+
+#[derive(Debug)]
+pub struct Point<A> {
+    pub coords: Vec2,
+    pub value: A
+}
+impl<A : Copy> Point<&A> {
+    pub fn copy(&self) -> Point<A> {
+        Point { coords: self.coords, value: *self.value }
+    }
+}
+
+impl<A : Display> Display for Point<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_fmt(format_args!("{}@{}", self.value, self.coords))
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Offset {
+    pub x: usize,
+    pub y: usize
+}
+impl Offset {
+    pub fn contramap_row(&self, row: usize) -> i64 {
+        (row as i64) - (self.x as i64)
+    }
+
+    pub fn contramap_col(&self, col: usize) -> i64 {
+        (col as i64) - (self.y as i64)
+    }
+
+    pub fn map(&self, row: i64, col: i64) -> Vec2 {
+        Vec2::new((row + self.x as i64) as usize, (col + self.y as i64) as usize)
+    }
+}
+
+/// Direction of a line through a grid: a row or a column.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Axis { Row, Col }
+
+/// A rectangular grid stored row by row in a single `Vec`, indexed by [Vec2] where `x` is the row
+/// and `y` is the column.
+#[derive(Clone)]
+pub struct GridMap<A> {
+    data: Vec<A>,
+    width: usize,
+    height: usize,
+    pub offset: Offset
+}
+impl<A> GridMap<A> {
+    pub fn new() -> Self { GridMap { data: Vec::new(), width: 0, height: 0, offset: Offset::default() } }
+
+    /// Panics if the rows are not all of the same length.
+    pub fn new_with_data(rows: Vec<Vec<A>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        assert!(rows.iter().all(|row| row.len() == width), "grid rows must all be {} long", width);
+        GridMap { data: rows.into_iter().flatten().collect(), width, height, offset: Offset::default() }
+    }
+
+    pub fn new_with_offset(offset: Offset) -> Self { GridMap { offset, ..Self::new() } }
+
+    /// A `height` × `width` grid with every cell computed from its coordinate.
+    pub fn from_fn(height: usize, width: usize, f: impl FnMut(Vec2) -> A) -> Self {
+        let data = (0..height).flat_map(|x| (0..width).map(move |y| Vec2::new(x, y))).map(f).collect();
+        GridMap { data, width, height, offset: Offset::default() }
+    }
+
+    /// Number of columns.
+    pub fn width(&self) -> usize { self.width }
+
+    /// Number of rows.
+    pub fn height(&self) -> usize { self.height }
+
+    fn index(&self, coord: &Vec2) -> Option<usize> {
+        if coord.x < self.height && coord.y < self.width { Some(coord.x * self.width + coord.y) } else { None }
+    }
+
+    pub fn row(&self, x: usize) -> Option<&[A]> {
+        if x < self.height { Some(&self.data[x * self.width..(x + 1) * self.width]) } else { None }
+    }
+
+    pub fn row_mut(&mut self, x: usize) -> Option<&mut [A]> {
+        if x < self.height { Some(&mut self.data[x * self.width..(x + 1) * self.width]) } else { None }
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[A]> + ExactSizeIterator {
+        (0..self.height).map(|x| self.row(x).unwrap())
+    }
+
+    pub fn column(&self, y: usize) -> Option<impl Iterator<Item = &A>> {
+        if y < self.width { Some(self.data.iter().skip(y).step_by(self.width)) } else { None }
+    }
+
+    /// All values, row by row.
+    pub fn values(&self) -> &[A] { &self.data }
+
+    pub fn values_mut(&mut self) -> &mut [A] { &mut self.data }
+
+    pub fn each_coord(&self) -> impl Iterator<Item = Vec2> {
+        let width = self.width;
+        (0..self.height).flat_map(move |x| (0..width).map(move |y| Vec2::new(x, y)))
+    }
+
+    pub fn each_point(&self) -> impl Iterator<Item = Point<&A>> {
+        self.each_coord().zip(self.data.iter()).map(|(coords, value)| Point { coords, value })
+    }
+
+    pub fn get(&self, coord: &Vec2) -> Option<&A> {
+        self.index(coord).map(|idx| &self.data[idx])
+    }
+
+    pub fn get_mut(&mut self, coord: &Vec2) -> Option<&mut A> {
+        self.index(coord).map(|idx| &mut self.data[idx])
+    }
+
+    pub fn get_mut_offset(&mut self, coord: &Vec2Signed) -> Option<&mut A> {
+        self.get_mut(&coord.to_vec2(&self.offset))
+    }
+
+    pub fn get_point(&self, coord: &Vec2) -> Option<Point<&A>> {
+        self.get(coord).map(|v| Point { coords: *coord, value: v} )
+    }
+
+    pub fn get_point_mut(&mut self, coord: &Vec2) -> Option<Point<&mut A>> {
+        self.get_mut(coord).map(|v| Point { coords: *coord, value: v} )
+    }
+
+    pub fn get1(&self, x: usize, y: usize) -> Option<&A> {
+        self.get(&Vec2::new(x, y))
+    }
+
+    /// The neighbour of `c` in `direction`.
+    pub fn get_towards(&self, c: &Vec2, direction: impl Into<Vec2Signed>) -> Option<&A> {
+        c.step(direction).and_then(|c| self.get(&c))
+    }
+
+    /// Drops every row from `height` on.
+    pub fn truncate_rows(&mut self, height: usize) {
+        if height < self.height {
+            self.data.truncate(height * self.width);
+            self.height = height;
+        }
+    }
+
+    /// Drops every column from `width` on.
+    pub fn truncate_columns(&mut self, width: usize) {
+        if width < self.width {
+            let old_width = self.width;
+            let mut idx = 0;
+            self.data.retain(|_| {
+                let keep = idx % old_width < width;
+                idx += 1;
+                keep
+            });
+            self.width = width;
+        }
+    }
+}
+impl<A : Clone> GridMap<A> {
+    /// A `height` × `width` grid with every cell set to `value`.
+    pub fn filled(height: usize, width: usize, value: A) -> Self {
+        GridMap { data: vec![value; height * width], width, height, offset: Offset::default() }
+    }
+
+    /// Grows the grid, filling new cells with `default_value`, so that `c` is within it.
+    pub fn ensure_indexes(&mut self, c: &Vec2, default_value: &A) {
+        let width = self.width.max(c.y + 1);
+        let height = self.height.max(c.x + 1);
+        if width == self.width && height == self.height { return }
+
+        if width == self.width {
+            self.data.resize(height * width, default_value.clone());
+        }
+        else {
+            let mut data = Vec::<A>::with_capacity(height * width);
+            for row in self.rows() {
+                data.extend_from_slice(row);
+                data.extend(std::iter::repeat_n(default_value, width - self.width).cloned());
+            }
+            data.resize(height * width, default_value.clone());
+            self.data = data;
+        }
+        self.width = width;
+        self.height = height;
+    }
+
+    pub fn ensure_indexes_offset(&mut self, c: &Vec2Signed, default_value: &A) {
+        self.ensure_indexes(&c.to_vec2(&self.offset), default_value)
+    }
+
+    // The transforms below build a new grid with the default offset.
+
+    /// Rows become columns, mirroring the grid along its main diagonal.
+    pub fn transpose(&self) -> Self {
+        GridMap::from_fn(self.width, self.height, |c| self.get1(c.y, c.x).unwrap().clone())
+    }
+
+    /// Rotates a quarter turn clockwise.
+    pub fn rotate_90(&self) -> Self {
+        GridMap::from_fn(self.width, self.height, |c| self.get1(self.height - 1 - c.y, c.x).unwrap().clone())
+    }
+
+    pub fn rotate_180(&self) -> Self {
+        GridMap::from_fn(self.height, self.width, |c| {
+            self.get1(self.height - 1 - c.x, self.width - 1 - c.y).unwrap().clone()
+        })
+    }
+
+    /// Rotates a quarter turn counterclockwise.
+    pub fn rotate_270(&self) -> Self {
+        GridMap::from_fn(self.width, self.height, |c| self.get1(c.y, self.width - 1 - c.x).unwrap().clone())
+    }
+
+    /// Mirrors along `axis`: [Axis::Row] turns the grid upside down, [Axis::Col] reverses every row.
+    pub fn mirror(&self, axis: Axis) -> Self {
+        GridMap::from_fn(self.height, self.width, |c| {
+            let from = match axis {
+                Axis::Row => Vec2::new(self.height - 1 - c.x, c.y),
+                Axis::Col => Vec2::new(c.x, self.width - 1 - c.y)
+            };
+            self.get(&from).unwrap().clone()
+        })
+    }
+
+    /// The cells from `from` to `to`, both inclusive, clamped to the grid.
+    pub fn crop(&self, from: Vec2, to: Vec2) -> Self {
+        let height = (to.x + 1).min(self.height).saturating_sub(from.x);
+        let width = (to.y + 1).min(self.width).saturating_sub(from.y);
+        GridMap::from_fn(height, width, |c| self.get(&(c + from)).unwrap().clone())
+    }
+
+    /// Folds the part after row or column `index` over onto the part before it, dropping the fold
+    /// line itself. Cells landing on each other are combined with `merge(kept, folded)`.
+    ///
+    /// When the folded part is the longer one the result grows to fit it, so the first rows or
+    /// columns then only hold folded cells. A fold line past the last row or column has nothing
+    /// after it to fold, so the result is a copy of the grid with its offset reset.
+    pub fn fold(&self, axis: Axis, index: usize, merge: impl Fn(&A, &A) -> A) -> Self {
+        let len = match axis { Axis::Row => self.height, Axis::Col => self.width };
+        if index >= len { return GridMap { offset: Offset::default(), ..self.clone() } }
+        let folded_len = index.max(len - index - 1);
+
+        // Distance from the fold line to a cell of the result.
+        let distance = |c: &Vec2| folded_len - match axis { Axis::Row => c.x, Axis::Col => c.y };
+        let along = |c: &Vec2, i: usize| match axis {
+            Axis::Row => Vec2::new(i, c.y),
+            Axis::Col => Vec2::new(c.x, i)
+        };
+        let (height, width) = match axis {
+            Axis::Row => (folded_len, self.width),
+            Axis::Col => (self.height, folded_len)
+        };
+        GridMap::from_fn(height, width, |c| {
+            let d = distance(&c);
+            let kept = index.checked_sub(d).and_then(|i| self.get(&along(&c, i)));
+            let folded = self.get(&along(&c, index + d));
+            match (kept, folded) {
+                (Some(kept), Some(folded)) => merge(kept, folded),
+                (Some(cell), None) | (None, Some(cell)) => cell.clone(),
+                (None, None) => unreachable!("{} is outside of both halves", c)
+            }
+        })
+    }
+}
+
+impl<A> Default for GridMap<A> {
+    fn default() -> Self { Self::new() }
+}
+
+/// Writes the cells with the row numbers on the left and the column numbers written vertically on
+/// top, rows and columns are printed in the order given.
+fn write_labeled<A : Display>(
+    f: &mut Formatter<'_>, rows: &[i64], cols: &[i64], cell: impl Fn(i64, i64) -> A
+) -> core::fmt::Result {
+    if rows.is_empty() || cols.is_empty() { return Ok(()) }
+
+    let row_width = rows.iter().map(|row| row.to_string().len()).max().unwrap_or(1);
+    let col_digits = cols.iter().map(|col| col.unsigned_abs().to_string().len()).max().unwrap_or(1);
+    let col_labels = cols.iter().map(|col| format!("{:0>width$}", col.unsigned_abs(), width = col_digits)).collect::<Vec<_>>();
+
+    let col_num_prefix = " ".repeat(row_width + 1);
+    if cols.iter().any(|col| *col < 0) {
+        f.write_str(&col_num_prefix)?;
+        for col in cols {
+            f.write_str(if *col < 0 { "-" } else { " " })?;
+        }
+        f.write_str("\n")?;
+    }
+    for digit in 0..col_digits {
+        f.write_str(&col_num_prefix)?;
+        for label in &col_labels {
+            f.write_str(&label[digit..digit + 1])?;
+        }
+        f.write_str("\n")?;
+    }
+
+    for row in rows {
+        f.write_fmt(format_args!("{:>width$} ", row, width = row_width))?;
+        for col in cols {
+            f.write_fmt(format_args!("{}", cell(*row, *col)))?;
+        }
+        f.write_str("\n")?;
+    }
+    Ok(())
+}
+
+impl<A : Display> Display for GridMap<A> {
+    /// Rows are printed bottom up if the offset makes some of them negative, so that puzzles where
+    /// rows grow upwards read naturally.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut rows = (0..self.height).map(|row| self.offset.contramap_row(row)).collect::<Vec<_>>();
+        if self.offset.contramap_row(0) < 0 { rows.reverse(); }
+        let cols = (0..self.width).map(|col| self.offset.contramap_col(col)).collect::<Vec<_>>();
+        write_labeled(f, &rows, &cols, |row, col| self.get(&self.offset.map(row, col)).unwrap())
+    }
+}
+
+/// A grid keyed by signed coordinates that grows in every direction as cells are written. Cells that
+/// were never written have the default value.
+#[derive(Clone)]
+pub struct InfiniteGrid<A> {
+    /// Allocated cells, which can be more than [InfiniteGrid::bounds] to make growing cheap.
+    cells: GridMap<A>,
+    /// Coordinates of the first allocated cell.
+    origin: Vec2Signed,
+    bounds: Option<Rect>,
+    default: A,
+    rows_up: bool
+}
+impl<A : Clone> InfiniteGrid<A> {
+    pub fn new(default: A) -> Self {
+        InfiniteGrid { cells: GridMap::new(), origin: Vec2Signed::new(0, 0), bounds: None, default, rows_up: false }
+    }
+
+    /// Renders rows with the highest row number first, for puzzles where rows grow upwards.
+    pub fn rows_up(self) -> Self { InfiniteGrid { rows_up: true, ..self } }
+
+    /// The smallest rectangle holding every coordinate that has been written or
+    /// [included](InfiniteGrid::include), `None` if there are none.
+    pub fn bounds(&self) -> Option<Rect> { self.bounds }
+
+    pub fn default_value(&self) -> &A { &self.default }
+
+    /// Changes the value of every cell outside of the bounds, for example the infinite background
+    /// of an image that flips on every step.
+    pub fn set_default(&mut self, default: A) {
+        for x in 0..self.cells.height() {
+            for y in 0..self.cells.width() {
+                let c = self.origin + Vec2Signed::new(x as i64, y as i64);
+                if !self.within_bounds(&c) { *self.cells.get_mut(&Vec2::new(x, y)).unwrap() = default.clone(); }
+            }
+        }
+        self.default = default;
+    }
+
+    pub fn within_bounds(&self, c: &Vec2Signed) -> bool {
+        self.bounds.is_some_and(|bounds| bounds.contains(c))
+    }
+
+    fn index(&self, c: &Vec2Signed) -> Option<Vec2> {
+        let x = usize::try_from(c.x - self.origin.x).ok()?;
+        let y = usize::try_from(c.y - self.origin.y).ok()?;
+        if x < self.cells.height() && y < self.cells.width() { Some(Vec2::new(x, y)) } else { None }
+    }
+
+    pub fn get(&self, c: &Vec2Signed) -> &A {
+        self.index(c).and_then(|idx| self.cells.get(&idx)).unwrap_or(&self.default)
+    }
+
+    /// Grows the grid to include `c` first.
+    pub fn get_mut(&mut self, c: &Vec2Signed) -> &mut A {
+        self.include(c);
+        let idx = self.index(c).unwrap();
+        self.cells.get_mut(&idx).unwrap()
+    }
+
+    pub fn set(&mut self, c: &Vec2Signed, value: A) {
+        *self.get_mut(c) = value;
+    }
+
+    /// Grows the bounds to include `c` without changing its value.
+    pub fn include(&mut self, c: &Vec2Signed) {
+        self.bounds = Some(self.bounds.map_or(Rect::point(*c), |bounds| bounds.include(c)));
+        if self.index(c).is_none() { self.reserve(c) }
+    }
+
+    /// Reallocates the cells so that `c` fits, at least doubling the size in the direction it grows
+    /// so that growing one cell at a time stays cheap.
+    fn reserve(&mut self, c: &Vec2Signed) {
+        let grow = |lo: i64, len: usize, v: i64| -> (i64, i64) {
+            if len == 0 { return (v, v) }
+            let len = len as i64;
+            let hi = lo + len - 1;
+            if v < lo { (v.min(lo - len), hi) }
+            else if v > hi { (lo, v.max(hi + len)) }
+            else { (lo, hi) }
+        };
+        let (row_lo, row_hi) = grow(self.origin.x, self.cells.height(), c.x);
+        let (col_lo, col_hi) = grow(self.origin.y, self.cells.width(), c.y);
+
+        let mut cells =
+            GridMap::filled((row_hi - row_lo + 1) as usize, (col_hi - col_lo + 1) as usize, self.default.clone());
+        for point in self.cells.each_point() {
+            let x = (self.origin.x - row_lo) as usize + point.coords.x;
+            let y = (self.origin.y - col_lo) as usize + point.coords.y;
+            *cells.get_mut(&Vec2::new(x, y)).unwrap() = point.value.clone();
+        }
+        self.cells = cells;
+        self.origin = Vec2Signed::new(row_lo, col_lo);
+    }
+
+    /// Every cell within the bounds, row by row.
+    pub fn each_point(&self) -> impl Iterator<Item = (Vec2Signed, &A)> {
+        self.bounds.into_iter().flat_map(|bounds| bounds.points()).map(|c| (c, self.get(&c)))
+    }
+}
+impl<A : Clone + Display> Display for InfiniteGrid<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let bounds = match self.bounds {
+            None => return Ok(()),
+            Some(bounds) => bounds
+        };
+        let mut rows = bounds.rows().collect::<Vec<_>>();
+        if self.rows_up { rows.reverse(); }
+        let cols = bounds.cols().collect::<Vec<_>>();
+        write_labeled(f, &rows, &cols, |row, col| self.get(&Vec2Signed::new(row, col)))
+    }
+}
+
+/// Common interface of the grid representations: the dense [GridMap], a sparse `HashMap<Vec2, A>`
+/// and `ndarray::Array2<A>`. Coordinates are [Vec2], `x` being the row.
+pub trait Grid {
+    type Cell;
+
+    fn get(&self, c: &Vec2) -> Option<&Self::Cell>;
+
+    fn get_mut(&mut self, c: &Vec2) -> Option<&mut Self::Cell>;
+
+    /// Stores `value` at `c` and returns whether it is within the grid. Sparse grids take any `c`.
+    fn set(&mut self, c: &Vec2, value: Self::Cell) -> bool {
+        match self.get_mut(c) {
+            Some(cell) => { *cell = value; true }
+            None => false
+        }
+    }
+
+    /// `(rows, columns)`, every coordinate of the grid is below them.
+    fn bounds(&self) -> (usize, usize);
+
+    /// Whether `c` is within the bounds, sparse grids only contain the coordinates with a value.
+    fn contains(&self, c: &Vec2) -> bool {
+        let (rows, cols) = self.bounds();
+        c.x < rows && c.y < cols
+    }
+
+    /// Every cell that has a value, row by row for dense grids and in no particular order for sparse
+    /// ones.
+    fn points(&self) -> impl Iterator<Item = (Vec2, &Self::Cell)>;
+
+    /// Coordinates next to `c` that the grid [contains](Grid::contains).
+    fn neighbors(&self, c: &Vec2, diagonals: bool) -> impl Iterator<Item = Vec2> {
+        let (rows, cols) = self.bounds();
+        c.adjacent(diagonals).filter(move |n| n.x < rows && n.y < cols)
+    }
+
+    /// Renders every coordinate within the bounds row by row, `cell` gets `None` for the holes of
+    /// sparse grids.
+    fn render(&self, cell: impl Fn(Option<&Self::Cell>) -> String) -> String {
+        let (rows, cols) = self.bounds();
+        let mut out = String::new();
+        for x in 0..rows {
+            for y in 0..cols {
+                out.push_str(&cell(self.get(&Vec2::new(x, y))));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// [Grid::render] with the cells' [Display], holes are rendered as spaces.
+    fn render_values(&self) -> String where Self::Cell : Display {
+        self.render(|cell| cell.map_or(String::from(" "), |cell| cell.to_string()))
+    }
+
+    fn to_sparse(&self) -> HashMap<Vec2, Self::Cell> where Self::Cell : Clone {
+        self.points().map(|(c, cell)| (c, cell.clone())).collect()
+    }
+
+    /// Holes of sparse grids become `default`.
+    fn to_grid_map(&self, default: Self::Cell) -> GridMap<Self::Cell> where Self::Cell : Clone {
+        let (rows, cols) = self.bounds();
+        let mut map = GridMap::filled(rows, cols, default);
+        for (c, cell) in self.points() {
+            *map.get_mut(&c).unwrap() = cell.clone();
+        }
+        map
+    }
+
+    /// Holes of sparse grids become `default`.
+    fn to_array2(&self, default: Self::Cell) -> Array2<Self::Cell> where Self::Cell : Clone {
+        let (rows, cols) = self.bounds();
+        let mut array = Array2::from_elem((rows, cols), default);
+        for (c, cell) in self.points() {
+            array[c.as_tuple()] = cell.clone();
+        }
+        array
+    }
+}
+
+impl<A> Grid for GridMap<A> {
+    type Cell = A;
+
+    fn get(&self, c: &Vec2) -> Option<&A> { GridMap::get(self, c) }
+
+    fn get_mut(&mut self, c: &Vec2) -> Option<&mut A> { GridMap::get_mut(self, c) }
+
+    fn bounds(&self) -> (usize, usize) { (self.height, self.width) }
+
+    fn points(&self) -> impl Iterator<Item = (Vec2, &A)> {
+        self.each_point().map(|p| (p.coords, p.value))
+    }
+}
+
+impl<A> Grid for HashMap<Vec2, A> {
+    type Cell = A;
+
+    fn get(&self, c: &Vec2) -> Option<&A> { HashMap::get(self, c) }
+
+    fn get_mut(&mut self, c: &Vec2) -> Option<&mut A> { HashMap::get_mut(self, c) }
+
+    fn set(&mut self, c: &Vec2, value: A) -> bool {
+        self.insert(*c, value);
+        true
+    }
+
+    /// Scans every key, [Grid::contains] and [Grid::neighbors] look the coordinates up instead.
+    fn bounds(&self) -> (usize, usize) {
+        let rows = self.keys().map(|c| c.x + 1).max().unwrap_or(0);
+        let cols = self.keys().map(|c| c.y + 1).max().unwrap_or(0);
+        (rows, cols)
+    }
+
+    fn contains(&self, c: &Vec2) -> bool { self.contains_key(c) }
+
+    fn points(&self) -> impl Iterator<Item = (Vec2, &A)> {
+        self.iter().map(|(c, cell)| (*c, cell))
+    }
+
+    fn neighbors(&self, c: &Vec2, diagonals: bool) -> impl Iterator<Item = Vec2> {
+        c.adjacent(diagonals).filter(|n| self.contains_key(n))
+    }
+}
+
+impl<A> Grid for Array2<A> {
+    type Cell = A;
+
+    fn get(&self, c: &Vec2) -> Option<&A> { ArrayBase::get(self, c.as_tuple()) }
+
+    fn get_mut(&mut self, c: &Vec2) -> Option<&mut A> { ArrayBase::get_mut(self, c.as_tuple()) }
+
+    fn bounds(&self) -> (usize, usize) { self.dim() }
+
+    fn points(&self) -> impl Iterator<Item = (Vec2, &A)> {
+        self.indexed_iter().map(|((x, y), cell)| (Vec2::new(x, y), cell))
+    }
+}
+
+impl<A> From<Array2<A>> for GridMap<A> {
+    fn from(array: Array2<A>) -> Self {
+        let (height, width) = array.dim();
+        GridMap { data: array.into_iter().collect(), width, height, offset: Offset::default() }
+    }
+}
+
+impl<A> From<GridMap<A>> for Array2<A> {
+    fn from(map: GridMap<A>) -> Self {
+        Array2::from_shape_vec((map.height, map.width), map.data).unwrap()
+    }
+}
+
+/// Read only access to cells by value, which also covers grids that compute their cells on the fly
+/// like [Tiled]. Every [Grid] with `Clone` cells is one.
+pub trait GridView {
+    type Cell;
+
+    /// `(rows, columns)`, see [Grid::bounds].
+    fn bounds(&self) -> (usize, usize);
+
+    fn cell(&self, c: &Vec2) -> Option<Self::Cell>;
+
+    /// Coordinates next to `c` that are within the bounds.
+    fn neighbors(&self, c: &Vec2, diagonals: bool) -> impl Iterator<Item = Vec2> {
+        let (rows, cols) = self.bounds();
+        c.adjacent(diagonals).filter(move |n| n.x < rows && n.y < cols)
+    }
+}
+impl<G : Grid> GridView for G where G::Cell : Clone {
+    type Cell = G::Cell;
+
+    fn bounds(&self) -> (usize, usize) { Grid::bounds(self) }
+
+    fn cell(&self, c: &Vec2) -> Option<Self::Cell> { self.get(c).cloned() }
+
+    fn neighbors(&self, c: &Vec2, diagonals: bool) -> impl Iterator<Item = Vec2> { Grid::neighbors(self, c, diagonals) }
+}
+
+/// A grid repeated `rows` × `cols` times without materialising the copies. `tile` computes a cell
+/// of a copy from the original cell and the `(row, col)` index of the copy.
+pub struct Tiled<'a, G, F> {
+    grid: &'a G,
+    rows: usize,
+    cols: usize,
+    tile: F
+}
+impl<'a, G : Grid, F : Fn(&G::Cell, (usize, usize)) -> G::Cell> Tiled<'a, G, F> {
+    pub fn new(grid: &'a G, rows: usize, cols: usize, tile: F) -> Self {
+        Tiled { grid, rows, cols, tile }
+    }
+}
+impl<G : Grid, F : Fn(&G::Cell, (usize, usize)) -> G::Cell> GridView for Tiled<'_, G, F> {
+    type Cell = G::Cell;
+
+    fn bounds(&self) -> (usize, usize) {
+        let (rows, cols) = self.grid.bounds();
+        (rows * self.rows, cols * self.cols)
+    }
+
+    fn cell(&self, c: &Vec2) -> Option<Self::Cell> {
+        let (rows, cols) = self.grid.bounds();
+        if rows == 0 || cols == 0 || c.x >= rows * self.rows || c.y >= cols * self.cols { return None }
+        let original = self.grid.get(&Vec2::new(c.x % rows, c.y % cols))?;
+        Some((self.tile)(original, (c.x / rows, c.y / cols)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use super::*;
+
+    fn rows_of<A : Clone>(grid: &GridMap<A>) -> Vec<Vec<A>> {
+        grid.rows().map(|row| row.to_vec()).collect()
+    }
+
+    /// Every cell holds its row and column as `"xy"`.
+    fn labeled(height: usize, width: usize) -> GridMap<String> {
+        GridMap::from_fn(height, width, |c| format!("{}{}", c.x, c.y))
+    }
+
+    #[test]
+    fn ensure_indexes_grows() {
+        let mut grid = GridMap::new_with_data(vec![vec![1, 2], vec![3, 4]]);
+        grid.ensure_indexes(&Vec2::new(1, 3), &0);
+        assert_eq!(rows_of(&grid), [[1, 2, 0, 0], [3, 4, 0, 0]]);
+        grid.ensure_indexes(&Vec2::new(2, 0), &9);
+        assert_eq!(rows_of(&grid), [vec![1, 2, 0, 0], vec![3, 4, 0, 0], vec![9, 9, 9, 9]]);
+        grid.ensure_indexes(&Vec2::new(3, 4), &7);
+        assert_eq!((grid.height(), grid.width()), (4, 5));
+        assert_eq!(grid.row(0), Some(&[1, 2, 0, 0, 7][..]));
+        assert_eq!(grid.row(3), Some(&[7; 5][..]));
+
+        // Coordinates within the grid change nothing.
+        grid.ensure_indexes(&Vec2::new(1, 1), &5);
+        assert_eq!((grid.height(), grid.width()), (4, 5));
+
+        let mut empty = GridMap::new();
+        empty.ensure_indexes(&Vec2::new(1, 2), &'.');
+        assert_eq!(rows_of(&empty), [['.'; 3]; 2]);
+    }
+
+    #[test]
+    fn truncate() {
+        let mut grid = labeled(3, 4);
+        grid.truncate_columns(2);
+        assert_eq!(rows_of(&grid), [["00", "01"], ["10", "11"], ["20", "21"]]);
+        grid.truncate_rows(1);
+        assert_eq!(rows_of(&grid), [["00", "01"]]);
+
+        // Truncating to more than there is keeps the grid.
+        grid.truncate_columns(5);
+        grid.truncate_rows(5);
+        assert_eq!(rows_of(&grid), [["00", "01"]]);
+        grid.truncate_columns(0);
+        assert_eq!((grid.height(), grid.width(), grid.values().len()), (1, 0, 0));
+    }
+
+    #[test]
+    fn columns() {
+        let grid = labeled(3, 2);
+        assert_eq!(grid.column(1).unwrap().collect::<Vec<_>>(), ["01", "11", "21"]);
+        assert!(grid.column(2).is_none());
+        assert!(grid.row(3).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "grid rows must all be 2 long")]
+    fn ragged_rows_are_rejected() {
+        GridMap::new_with_data(vec![vec![1, 2], vec![3], vec![4, 5]]);
+    }
+
+    #[test]
+    fn sparse_and_dense_conversions() {
+        let grid = GridMap::new_with_data(vec![vec![1, 0, 2], vec![0, 3, 0]]);
+        let sparse = grid.to_sparse();
+        assert_eq!(sparse.len(), 6);
+        assert_eq!(rows_of(&sparse.to_grid_map(9)), rows_of(&grid));
+
+        let holes = sparse.into_iter().filter(|(_, v)| *v != 0).collect::<HashMap<_, _>>();
+        assert_eq!(Grid::bounds(&holes), (2, 3));
+        assert_eq!(rows_of(&holes.to_grid_map(0)), rows_of(&grid));
+        assert_eq!(holes.render_values(), "1 2\n 3 \n");
+
+        let array = grid.to_array2(0);
+        assert_eq!(array, ndarray::arr2(&[[1, 0, 2], [0, 3, 0]]));
+        assert_eq!(rows_of(&GridMap::from(array.clone())), rows_of(&grid));
+        assert_eq!(Array2::from(GridMap::from(array.clone())), array);
+    }
+
+    #[test]
+    fn sparse_neighbors_are_the_coordinates_with_a_value() {
+        let sparse = [(0, 0), (0, 1), (1, 1), (5, 5)].into_iter()
+            .map(|(x, y)| (Vec2::new(x, y), ()))
+            .collect::<HashMap<_, _>>();
+        assert!(Grid::contains(&sparse, &Vec2::new(1, 1)));
+        assert!(!Grid::contains(&sparse, &Vec2::new(1, 0)));
+        assert_eq!(
+            Grid::neighbors(&sparse, &Vec2::new(0, 0), false).collect::<HashSet<_>>(), HashSet::from([Vec2::new(0, 1)])
+        );
+        assert_eq!(
+            GridView::neighbors(&sparse, &Vec2::new(1, 0), true).collect::<HashSet<_>>(),
+            HashSet::from([Vec2::new(0, 0), Vec2::new(0, 1), Vec2::new(1, 1)])
+        );
+    }
+
+    #[test]
+    fn fold_keeps_the_longer_half() {
+        let grid = GridMap::from_fn(7, 1, |c| c.x as u32);
+        let merge = |kept: &u32, folded: &u32| kept * 100 + folded;
+
+        // Rows 3 to 6 fold onto rows 1 and 0, the first two rows only hold folded cells.
+        assert_eq!(rows_of(&grid.fold(Axis::Row, 2, merge)), [[6], [5], [4], [103]]);
+        // Only row 6 folds onto row 4.
+        assert_eq!(rows_of(&grid.fold(Axis::Row, 5, merge)), [[0], [1], [2], [3], [406]]);
+        assert_eq!(rows_of(&grid.transpose().fold(Axis::Col, 2, merge)), [[6, 5, 4, 103]]);
+    }
+
+    #[test]
+    fn fold_past_the_end_keeps_the_grid() {
+        let mut grid = labeled(3, 2);
+        grid.offset = Offset { x: 1, y: 1 };
+        for index in [2, 3, 10] {
+            assert_eq!(rows_of(&grid.fold(Axis::Col, index, |a, _| a.clone())), rows_of(&grid));
+        }
+        let folded = grid.fold(Axis::Row, 3, |a, _| a.clone());
+        assert_eq!(rows_of(&folded), rows_of(&grid));
+        assert_eq!(folded.offset, Offset::default());
+    }
+
+    #[test]
+    fn rotations() {
+        let grid = labeled(2, 3);
+        assert_eq!(rows_of(&grid.rotate_90()), [["10", "00"], ["11", "01"], ["12", "02"]]);
+        assert_eq!(rows_of(&grid.rotate_90().rotate_90()), rows_of(&grid.rotate_180()));
+        assert_eq!(rows_of(&grid.rotate_90().rotate_90().rotate_90()), rows_of(&grid.rotate_270()));
+        assert_eq!(rows_of(&grid.rotate_90().rotate_90().rotate_90().rotate_90()), rows_of(&grid));
+        assert_eq!(rows_of(&grid.rotate_270().rotate_90()), rows_of(&grid));
+    }
+
+    #[test]
+    fn mirror_and_transpose_are_their_own_inverse() {
+        let grid = labeled(2, 3);
+        assert_eq!(rows_of(&grid.mirror(Axis::Row)), [["10", "11", "12"], ["00", "01", "02"]]);
+        assert_eq!(rows_of(&grid.mirror(Axis::Col)), [["02", "01", "00"], ["12", "11", "10"]]);
+        for axis in [Axis::Row, Axis::Col] {
+            assert_eq!(rows_of(&grid.mirror(axis).mirror(axis)), rows_of(&grid));
+        }
+        assert_eq!(rows_of(&grid.transpose().transpose()), rows_of(&grid));
+    }
+
+    #[test]
+    fn infinite_grid_grows_in_every_direction() {
+        let mut grid = InfiniteGrid::new(0);
+        let written = [(0, 0, 1), (-5, 3, 2), (4, -7, 3), (10, 10, 4)];
+        for (x, y, value) in written {
+            grid.set(&Vec2Signed::new(x, y), value);
+        }
+
+        assert_eq!(grid.bounds(), Some(Rect::new(Vec2Signed::new(-5, -7), Vec2Signed::new(10, 10))));
+        for (x, y, value) in written {
+            assert_eq!(*grid.get(&Vec2Signed::new(x, y)), value);
+        }
+        assert_eq!(*grid.get(&Vec2Signed::new(1, 1)), 0);
+        assert_eq!(*grid.get(&Vec2Signed::new(-100, 100)), 0);
+        assert_eq!(grid.each_point().filter(|(_, value)| **value != 0).count(), written.len());
+    }
+}
@@ -0,0 +1,15 @@
+//! Puzzle independent building blocks shared by the days: grids and their rendering, geometry, path
+//! finding, cellular automata, bit twiddling, input parsing and the [solution::Solution] trait every
+//! day implements.
+
+pub mod automaton;
+pub mod bits;
+pub mod flood_fill;
+pub mod geometry;
+pub mod grid;
+pub mod math;
+pub mod parsing;
+pub mod pathfinding;
+pub mod render;
+pub mod segments;
+pub mod solution;
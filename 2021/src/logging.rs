@@ -0,0 +1,46 @@
+use std::io::IsTerminal;
+use tracing_subscriber::EnvFilter;
+
+/// Environment variable holding a [tracing filter](EnvFilter), for example `AOC_LOG=aoc::day16=trace`
+/// or `AOC_LOG='[day{day=16}]=debug'`. Takes precedence over the `-v` flags.
+pub const LOG_ENV: &str = "AOC_LOG";
+
+/// Crates whose output `-v` turns up, dependencies stay at warnings.
+const CRATES: [&str; 2] = ["aoc", "advent_of_code"];
+
+/// The filter used without `AOC_LOG`: only warnings, `-v` enables debug and `-vv` trace output of
+/// the puzzles and the library.
+fn default_filter(verbosity: u8) -> String {
+    let level = match verbosity {
+        0 => return String::from("warn"),
+        1 => "debug",
+        _ => "trace"
+    };
+    CRATES.iter().fold(String::from("warn"), |filter, name| format!("{},{}={}", filter, name, level))
+}
+
+/// Installs the diagnostics subscriber, which writes to stderr so that stdout only has the answers.
+pub fn init(verbosity: u8) {
+    let filter = EnvFilter::try_from_env(LOG_ENV).unwrap_or_else(|_| EnvFilter::new(default_filter(verbosity)));
+    let ansi = std::io::stderr().is_terminal();
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_ansi(ansi)
+        // Let the colours of rendered grids through.
+        .with_ansi_sanitization(!ansi)
+        .without_time()
+        .init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbosity_only_affects_this_crate() {
+        assert_eq!(default_filter(0), "warn");
+        assert_eq!(default_filter(1), "warn,aoc=debug,advent_of_code=debug");
+        assert_eq!(default_filter(3), "warn,aoc=trace,advent_of_code=trace");
+    }
+}
@@ -1,22 +1,8 @@
 #![allow(dead_code)]
-#![allow(unused_imports)]
-#![feature(drain_filter)]
-#![feature(int_abs_diff)]
-#![feature(is_some_with)]
-#![feature(option_result_contains)]
-#![feature(int_log)]
 
 extern crate alloc;
 extern crate core;
 
-use std::fmt::{Debug, Display};
-use std::fs::File;
-use std::io::{BufRead, BufReader, Lines};
-use std::iter::Map;
-use std::ops::{Add, Div, Mul, Sub};
-use std::process::Output;
-use std::str::FromStr;
-
 mod day1;
 mod day2;
 mod day3;
@@ -34,30 +20,15 @@ mod day14;
 mod day15;
 mod day16;
 mod day17;
+mod registry;
+mod cli;
+mod check;
+mod bench;
+mod logging;
 
 fn main() {
-    day17::part1();
-}
-
-pub fn read_lines(path: &str) -> Map<Lines<BufReader<File>>, fn(std::io::Result<String>) -> String> {
-    let file = File::open(path).unwrap();
-    BufReader::new(file).lines().map(|line| line.unwrap())
-}
-
-pub fn read_num_line<A : FromStr>(path: &str) -> Vec<A> where <A as FromStr>::Err : Debug {
-    let line = read_lines(path).next().unwrap();
-    line.split(",").map(|s| s.parse::<A>().unwrap()).collect()
-}
-
-pub fn arithmetic_sum<
-    A : Copy + From<u8> + PartialOrd +
-    Add<Output = A> + Sub<Output = A> + Div<Output = A> + Mul<Output = A>
->(from: A, to_exclusive: A) -> Option<A> {
-    if to_exclusive <= from { None }
-    else {
-        let to = to_exclusive - 1.into();
-        let count = to_exclusive - from;
-        let two: A = 2.into();
-        Some(count * (from + to) / two)
+    if let Err(err) = cli::run(std::env::args().skip(1)) {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
     }
-}
\ No newline at end of file
+}
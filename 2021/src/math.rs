@@ -0,0 +1,292 @@
+use core::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Index, IndexMut, Mul, Sub};
+use num_traits::{One, Zero};
+
+pub fn arithmetic_sum<
+    A : Copy + From<u8> + PartialOrd +
+    Add<Output = A> + Sub<Output = A> + Div<Output = A> + Mul<Output = A>
+>(from: A, to_exclusive: A) -> Option<A> {
+    if to_exclusive <= from { None }
+    else {
+        let to = to_exclusive - 1.into();
+        let count = to_exclusive - from;
+        let two: A = 2.into();
+        Some(count * (from + to) / two)
+    }
+}
+
+/// Greatest common divisor, `gcd(0, 0)` being 0.
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 { (a, b) = (b, a % b); }
+    a
+}
+
+/// A square matrix, for linear recurrences that [Matrix::pow] can jump far ahead in.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Matrix<A> {
+    size: usize,
+    /// Row by row.
+    data: Vec<A>
+}
+impl<A : Clone + Zero + One> Matrix<A> {
+    pub fn zeros(size: usize) -> Self { Matrix { size, data: vec![A::zero(); size * size] } }
+
+    pub fn identity(size: usize) -> Self {
+        let mut matrix = Self::zeros(size);
+        for i in 0..size { matrix[(i, i)] = A::one(); }
+        matrix
+    }
+
+    pub fn size(&self) -> usize { self.size }
+
+    pub fn mul(&self, other: &Matrix<A>) -> Matrix<A> {
+        assert_eq!(self.size, other.size, "matrix sizes differ");
+        let mut result = Self::zeros(self.size);
+        for row in 0..self.size {
+            for k in 0..self.size {
+                if self[(row, k)].is_zero() { continue }
+                for col in 0..self.size {
+                    let product = self[(row, k)].clone() * other[(k, col)].clone();
+                    result[(row, col)] = result[(row, col)].clone() + product;
+                }
+            }
+        }
+        result
+    }
+
+    /// The matrix times itself `exp` times, in `O(log exp)` multiplications.
+    pub fn pow(&self, mut exp: u64) -> Matrix<A> {
+        let mut result = Self::identity(self.size);
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 { result = result.mul(&base); }
+            exp >>= 1;
+            if exp > 0 { base = base.mul(&base); }
+        }
+        result
+    }
+
+    /// The matrix times the column vector `v`.
+    pub fn apply(&self, v: &[A]) -> Vec<A> {
+        assert_eq!(self.size, v.len(), "vector size differs from the matrix size");
+        (0..self.size).map(|row| {
+            (0..self.size).fold(A::zero(), |sum, col| sum + self[(row, col)].clone() * v[col].clone())
+        }).collect()
+    }
+}
+impl<A> Index<(usize, usize)> for Matrix<A> {
+    type Output = A;
+
+    fn index(&self, (row, col): (usize, usize)) -> &A { &self.data[row * self.size + col] }
+}
+impl<A> IndexMut<(usize, usize)> for Matrix<A> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut A { &mut self.data[row * self.size + col] }
+}
+
+/// Timers counting down one per day, like the lanternfish's: a timer that runs out restarts at
+/// `reset` and starts a new timer at `newborn`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Timers { reset: usize, newborn: usize }
+impl Timers {
+    pub const fn new(reset: usize, newborn: usize) -> Self { Timers { reset, newborn } }
+
+    /// Number of distinct timer values, from 0 up to the larger of `reset` and `newborn`.
+    pub fn states(&self) -> usize { self.reset.max(self.newborn) + 1 }
+
+    /// How one day changes the number of timers with each value.
+    pub fn transition<A : Clone + Zero + One>(&self) -> Matrix<A> {
+        let mut matrix = Matrix::zeros(self.states());
+        for timer in 1..self.states() {
+            matrix[(timer - 1, timer)] = A::one();
+        }
+        matrix[(self.reset, 0)] = A::one();
+        matrix[(self.newborn, 0)] = matrix[(self.newborn, 0)].clone() + A::one();
+        matrix
+    }
+}
+
+/// Number of timers after `days` starting from `initial`, by raising [Timers::transition] to the
+/// power of `days`.
+///
+/// Panics if an initial timer is not below [Timers::states].
+pub fn population<A : Clone + Zero + One>(initial: &[usize], timers: &Timers, days: u64) -> A {
+    let mut counts = vec![A::zero(); timers.states()];
+    for timer in initial {
+        assert!(*timer < counts.len(), "timer {} is beyond the timers {:?}", timer, timers);
+        counts[*timer] = counts[*timer].clone() + A::one();
+    }
+
+    let transition = timers.transition::<A>().pow(days);
+    transition.apply(&counts).into_iter().fold(A::zero(), |sum, count| sum + count)
+}
+
+/// A number modulo `M`, for counts that would otherwise grow without bounds. `M` must not be 0:
+/// `Modular<0>` fails to compile as soon as a value is reduced.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Modular<const M: u64>(u64);
+impl<const M: u64> Modular<M> {
+    pub fn new(v: u64) -> Self { Self::reduce(v as u128) }
+
+    fn reduce(v: u128) -> Self {
+        const { assert!(M != 0, "the modulus must not be 0") };
+        Modular((v % M as u128) as u64)
+    }
+
+    pub fn value(&self) -> u64 { self.0 }
+
+    /// The number times itself `exp` times, in `O(log exp)` multiplications.
+    pub fn pow(&self, mut exp: u64) -> Self {
+        let mut result = Self::one();
+        let mut base = *self;
+        while exp > 0 {
+            if exp & 1 == 1 { result = result * base; }
+            exp >>= 1;
+            base = base * base;
+        }
+        result
+    }
+
+    /// The number that gives 1 when multiplied with this one, `None` if this one shares a factor with
+    /// `M`.
+    pub fn inverse(&self) -> Option<Self> {
+        // Extended Euclid, keeping only the coefficient of `self`.
+        let (mut r, mut next_r) = (M as i128, self.0 as i128);
+        let (mut t, mut next_t) = (0i128, 1i128);
+        while next_r != 0 {
+            let q = r / next_r;
+            (r, next_r) = (next_r, r - q * next_r);
+            (t, next_t) = (next_t, t - q * next_t);
+        }
+        if r == 1 { Some(Modular(t.rem_euclid(M as i128) as u64)) } else { None }
+    }
+}
+impl<const M: u64> Add for Modular<M> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output { Self::reduce(self.0 as u128 + rhs.0 as u128) }
+}
+impl<const M: u64> Mul for Modular<M> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output { Self::reduce(self.0 as u128 * rhs.0 as u128) }
+}
+impl<const M: u64> Zero for Modular<M> {
+    fn zero() -> Self { Modular(0) }
+    fn is_zero(&self) -> bool { self.0 == 0 }
+}
+impl<const M: u64> One for Modular<M> {
+    fn one() -> Self { Modular::new(1) }
+}
+impl<const M: u64> Display for Modular<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} (mod {})", self.0, M)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use super::*;
+
+    #[test]
+    fn pow_matches_repeated_mul() {
+        let mut matrix = Matrix::<u64>::zeros(3);
+        for (idx, v) in [1, 2, 0, 0, 1, 3, 1, 0, 1].into_iter().enumerate() {
+            matrix[(idx / 3, idx % 3)] = v;
+        }
+        let mut product = Matrix::identity(3);
+        for exp in 0..20 {
+            assert_eq!(matrix.pow(exp), product, "power {}", exp);
+            product = product.mul(&matrix);
+        }
+    }
+
+    /// Counts the timers one day at a time, for `newborn` being the highest timer value.
+    fn population_day_by_day(initial: &[usize], timers: &Timers, days: u64) -> BigUint {
+        let mut counts = vec![BigUint::zero(); timers.states()];
+        for timer in initial { counts[*timer] += 1u32; }
+        for _ in 0..days {
+            counts.rotate_left(1);
+            let births = counts[timers.states() - 1].clone();
+            counts[timers.reset] += births;
+        }
+        counts.into_iter().sum()
+    }
+
+    #[test]
+    fn population_matches_day_by_day() {
+        let initial = [3, 4, 3, 1, 2];
+        let timers = Timers::new(6, 8);
+        assert_eq!(population::<BigUint>(&initial, &timers, 80), BigUint::from(5934u32));
+        assert_eq!(population::<BigUint>(&initial, &timers, 256), BigUint::from(26984457539u64));
+        for days in [0, 1, 18, 80, 256] {
+            assert_eq!(
+                population::<BigUint>(&initial, &timers, days), population_day_by_day(&initial, &timers, days),
+                "after {} days", days
+            );
+        }
+    }
+
+    /// The largest prime below 2^64.
+    const BIG: u64 = u64::MAX - 58;
+
+    #[test]
+    fn modular_wraps_around() {
+        assert_eq!(Modular::<7>::new(15).value(), 1);
+        assert_eq!((Modular::<7>::new(5) + Modular::new(4)).value(), 2);
+        assert_eq!((Modular::<7>::new(6) * Modular::new(6)).value(), 1);
+        assert_eq!(Modular::<7>::one() + Modular::new(6), Modular::zero());
+        assert_eq!(Modular::<1>::one().value(), 0);
+        assert_eq!(Modular::<7>::new(3).to_string(), "3 (mod 7)");
+
+        // Sums and products of values near `u64::MAX` must not overflow before they are reduced.
+        let minus_one = Modular::<BIG>::new(BIG - 1);
+        assert_eq!(Modular::<BIG>::new(u64::MAX).value(), 58);
+        assert_eq!((minus_one + minus_one).value(), BIG - 2);
+        assert_eq!((minus_one * minus_one).value(), 1);
+    }
+
+    #[test]
+    fn modular_pow_and_inverse() {
+        let two = Modular::<13>::new(2);
+        let mut product = Modular::one();
+        for exp in 0..30 {
+            assert_eq!(two.pow(exp), product, "power {}", exp);
+            product = product * two;
+        }
+        assert_eq!(Modular::<13>::zero().pow(0), Modular::one());
+        // Fermat's little theorem, for a prime modulus.
+        assert_eq!(Modular::<BIG>::new(123_456_789).pow(BIG - 1), Modular::one());
+
+        for v in 1..13 {
+            let v = Modular::<13>::new(v);
+            assert_eq!(v * v.inverse().unwrap(), Modular::one(), "{}", v);
+        }
+        assert_eq!(Modular::<13>::zero().inverse(), None);
+        assert_eq!(Modular::<12>::new(5).inverse(), Some(Modular::new(5)));
+        assert_eq!(Modular::<12>::new(4).inverse(), None);
+        let v = Modular::<BIG>::new(u64::MAX / 3);
+        assert_eq!(v * v.inverse().unwrap(), Modular::one());
+    }
+
+    #[test]
+    fn population_modulo() {
+        let initial = [3, 4, 3, 1, 2];
+        let timers = Timers::new(6, 8);
+        for days in [0, 18, 80, 256, 500] {
+            let expected = population_day_by_day(&initial, &timers, days) % 1000u32;
+            assert_eq!(population::<Modular<1000>>(&initial, &timers, days).value(), expected.try_into().unwrap());
+        }
+        assert_eq!(population::<Modular<BIG>>(&initial, &timers, 256).value(), 26984457539);
+    }
+
+    #[test]
+    fn population_with_other_timers() {
+        let initial = [0, 2, 3];
+        let timers = Timers::new(2, 3);
+        for days in [0, 1, 5, 40] {
+            assert_eq!(
+                population::<BigUint>(&initial, &timers, days), population_day_by_day(&initial, &timers, days),
+                "after {} days", days
+            );
+        }
+    }
+}
@@ -0,0 +1,487 @@
+use core::fmt::{Display, Formatter};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// Directory with the puzzle inputs, relative to the working directory.
+pub const DATA_DIR: &str = "data";
+
+/// Where to take a day's input from.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum InputSource {
+    /// The real puzzle input, `data/day<N>.txt`.
+    Real,
+    /// A named variant of the input, `data/day<N>_<name>.txt`, for example `test` or `test1`.
+    Variant(String),
+    /// An explicit file, regardless of the day.
+    File(PathBuf),
+    Stdin
+}
+impl InputSource {
+    /// The variant name `real` is an alias for [InputSource::Real].
+    pub fn variant(name: &str) -> Self {
+        if name == "real" { InputSource::Real } else { InputSource::Variant(String::from(name)) }
+    }
+
+    /// The file this source reads for the given day, `None` for stdin.
+    pub fn path(&self, day: u8) -> Option<PathBuf> {
+        match self {
+            InputSource::Real => Some(Path::new(DATA_DIR).join(format!("day{}.txt", day))),
+            InputSource::Variant(name) => Some(Path::new(DATA_DIR).join(format!("day{}_{}.txt", day, name))),
+            InputSource::File(path) => Some(path.clone()),
+            InputSource::Stdin => None
+        }
+    }
+
+    pub fn load(&self, day: u8) -> Result<Input, InputError> {
+        match self.path(day) {
+            Some(path) => Input::from_file(&path),
+            None => {
+                let path = Path::new("<stdin>");
+                let mut text = String::new();
+                std::io::stdin().read_to_string(&mut text).map_err(|err| InputError::io(path, err))?;
+                Ok(Input { path: path.to_path_buf(), text })
+            }
+        }
+    }
+}
+impl Display for InputSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            InputSource::Real => f.write_str("real"),
+            InputSource::Variant(name) => f.write_str(name),
+            InputSource::File(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => f.write_str("-")
+        }
+    }
+}
+
+/// Names of the inputs available for the given day in [DATA_DIR], `real` first.
+pub fn variants(day: u8) -> Vec<String> {
+    let prefix = format!("day{}_", day);
+    let mut variants = std::fs::read_dir(DATA_DIR).into_iter().flatten().flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let variant = name.strip_prefix(&prefix)?.strip_suffix(".txt")?;
+            Some(String::from(variant))
+        })
+        .collect::<Vec<_>>();
+    variants.sort();
+    if InputSource::Real.path(day).is_some_and(|path| path.exists()) {
+        variants.insert(0, String::from("real"));
+    }
+    variants
+}
+
+/// The raw text of a puzzle input and where it came from.
+#[derive(Debug, Clone)]
+pub struct Input {
+    pub path: PathBuf,
+    pub text: String
+}
+impl Input {
+    pub fn from_file(path: &Path) -> Result<Self, InputError> {
+        let text = std::fs::read_to_string(path).map_err(|err| InputError::io(path, err))?;
+        Ok(Input { path: path.to_path_buf(), text })
+    }
+
+    pub fn lines(&self) -> Lines {
+        Lines::new(&self.path, &self.text)
+    }
+}
+
+/// A malformed or unreadable puzzle input.
+///
+/// `line` and `column` are 1-based. `line` is `None` for errors about the file as a whole, `column`
+/// is `None` for errors about a line as a whole.
+#[derive(Debug)]
+pub struct InputError {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub expected: String,
+    pub found: Option<String>
+}
+impl InputError {
+    pub fn new(path: &Path, expected: impl Into<String>) -> Self {
+        InputError { path: path.to_path_buf(), line: None, column: None, expected: expected.into(), found: None }
+    }
+
+    pub fn io(path: &Path, err: std::io::Error) -> Self {
+        Self::new(path, format!("a readable file ({})", err))
+    }
+}
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some(line) = self.line { write!(f, ":{}", line)?; }
+        if let Some(column) = self.column { write!(f, ":{}", column)?; }
+        write!(f, ": expected {}", self.expected)?;
+        if let Some(found) = &self.found { write!(f, ", found {}", found)?; }
+        Ok(())
+    }
+}
+impl std::error::Error for InputError {}
+
+/// A single line of a puzzle input that knows where it came from, so errors can point at it.
+#[derive(Debug, Clone)]
+pub struct Line {
+    pub path: Rc<Path>,
+    /// 1-based.
+    pub number: usize,
+    pub text: String
+}
+impl Line {
+    pub fn as_str(&self) -> &str { &self.text }
+
+    /// 1-based column of `token`, `None` if it is not a slice of this line's text.
+    pub fn column_of(&self, token: &str) -> Option<usize> {
+        let offset = (token.as_ptr() as usize).checked_sub(self.text.as_ptr() as usize)?;
+        let end = offset.checked_add(token.len())?;
+        if end <= self.text.len() { Some(offset + 1) } else { None }
+    }
+
+    pub fn error_at(&self, column: usize, expected: impl Into<String>, found: Option<String>) -> InputError {
+        InputError {
+            path: self.path.to_path_buf(), line: Some(self.number), column: Some(column),
+            expected: expected.into(), found
+        }
+    }
+
+    /// Error pointing at `token`, which should be a slice of this line's text. For any other token
+    /// the error is about the whole line.
+    pub fn error(&self, token: &str, expected: impl Into<String>) -> InputError {
+        InputError { column: self.column_of(token), ..self.error_at(1, expected, Some(format!("'{}'", token))) }
+    }
+
+    /// Error pointing at the character at byte index `idx` of `token`, which must be a slice of this
+    /// line's text.
+    pub fn error_at_char(&self, token: &str, idx: usize, expected: impl Into<String>) -> InputError {
+        let len = token[idx..].chars().next().map_or(0, |c| c.len_utf8());
+        self.error(&token[idx..idx + len], expected)
+    }
+
+    /// Error for a token that is missing at the end of the line.
+    pub fn error_at_end(&self, expected: impl Into<String>) -> InputError {
+        self.error_at(self.text.len() + 1, expected, Some(String::from("end of line")))
+    }
+
+    /// Turns a missing token into an [InputError::error_at_end].
+    pub fn token<'a>(&self, token: Option<&'a str>, expected: &str) -> Result<&'a str, InputError> {
+        token.ok_or_else(|| self.error_at_end(expected))
+    }
+
+    /// Parses `token`, which must be a slice of this line's text.
+    pub fn parse_token<A : FromStr>(&self, token: &str, expected: &str) -> Result<A, InputError> {
+        token.parse::<A>().map_err(|_| self.error(token, expected))
+    }
+
+    /// Parses every one of `tokens`, for example the placeholders returned by [Line::extract].
+    pub fn parse_tokens<A : FromStr, const N: usize>(&self, tokens: [&str; N], expected: &str) -> Result<[A; N], InputError> {
+        let parsed = tokens.iter().map(|token| self.parse_token(token, expected)).collect::<Result<Vec<A>, _>>()?;
+        Ok(parsed.try_into().unwrap_or_else(|_| unreachable!("parsed as many tokens as given")))
+    }
+
+    /// Parses a missing or present token, see [Line::token] and [Line::parse_token].
+    pub fn parse_next<A : FromStr>(&self, token: Option<&str>, expected: &str) -> Result<A, InputError> {
+        self.parse_token(self.token(token, expected)?, expected)
+    }
+
+    /// Parses every character of the line as a decimal digit.
+    pub fn digits(&self) -> Result<Vec<u32>, InputError> {
+        self.chars_with(|c| c.to_digit(10), "a digit")
+    }
+
+    /// Maps every character of the line with `f`, which returns `None` for characters that are not
+    /// `expected`.
+    pub fn chars_with<A>(&self, f: impl Fn(char) -> Option<A>, expected: &str) -> Result<Vec<A>, InputError> {
+        self.text.char_indices().map(|(idx, c)|
+            f(c).ok_or_else(|| self.error_at_char(self.as_str(), idx, expected))
+        ).collect()
+    }
+
+    /// Splits `token`, which must be a slice of this line's text, into exactly `N` characters.
+    pub fn char_array<const N: usize>(&self, token: &str, expected: &str) -> Result<[char; N], InputError> {
+        let chars = token.chars().collect::<Vec<_>>();
+        <[char; N]>::try_from(chars).map_err(|_| self.error(token, expected))
+    }
+
+    /// Parses `token`, which must be a slice of this line's text, as a list separated by
+    /// `delimiter`. A delimiter of `" "` splits on runs of whitespace, so aligned columns work too.
+    pub fn list<A : FromStr>(&self, token: &str, delimiter: &str, expected: &str) -> Result<Vec<A>, InputError> {
+        if delimiter == " " {
+            token.split_whitespace().map(|s| self.parse_token(s, expected)).collect()
+        }
+        else {
+            token.split(delimiter).map(|s| self.parse_token(s, expected)).collect()
+        }
+    }
+
+    /// Matches the whole line against `pattern` and returns the text of its `{}` placeholders, for
+    /// example `line.extract("{} -> {}")` for `AB -> C`. See [Line::extract_in].
+    pub fn extract<const N: usize>(&self, pattern: &str) -> Result<[&str; N], InputError> {
+        self.extract_in(self.as_str(), pattern)
+    }
+
+    /// Matches `token`, which must be a slice of this line's text, against `pattern` and returns the
+    /// text of its `N` `{}` placeholders. Every placeholder takes the shortest text up to the literal
+    /// that follows it, the last one takes the rest of the token if the pattern ends with it.
+    pub fn extract_in<'a, const N: usize>(&self, token: &'a str, pattern: &str) -> Result<[&'a str; N], InputError> {
+        let literals = pattern.split("{}").collect::<Vec<_>>();
+        assert_eq!(literals.len(), N + 1, "pattern '{}' must have {} placeholders", pattern, N);
+
+        let expected = || format!("'{}'", pattern);
+        let mismatch = |rest: &str| if rest.is_empty() { self.error_at_end(expected()) } else { self.error(rest, expected()) };
+
+        let mut rest = token.strip_prefix(literals[0]).ok_or_else(|| mismatch(token))?;
+        let mut captures = [""; N];
+        for (idx, literal) in literals[1..].iter().enumerate() {
+            let end =
+                if literal.is_empty() && idx == N - 1 { rest.len() }
+                else { rest.find(literal).ok_or_else(|| mismatch(rest))? };
+            captures[idx] = &rest[..end];
+            rest = &rest[end + literal.len()..];
+        }
+        if !rest.is_empty() { return Err(mismatch(rest)) }
+        Ok(captures)
+    }
+}
+
+/// Reads the first line as a comma separated list.
+pub fn read_num_line<A : FromStr>(mut lines: Lines) -> Result<Vec<A>, InputError> {
+    let line = lines.expect_line("a comma separated list of numbers")?;
+    line.list(line.as_str(), ",", "a number")
+}
+
+/// Lines of a puzzle input, see [Input::lines].
+pub struct Lines {
+    pub path: Rc<Path>,
+    lines: std::vec::IntoIter<Line>,
+    line_count: usize,
+    /// What running out of lines means, for errors.
+    end: &'static str
+}
+impl Lines {
+    pub fn new(path: &Path, text: &str) -> Self {
+        let path: Rc<Path> = Rc::from(path);
+        let lines = text.lines().enumerate().map(|(idx, text)|
+            Line { path: path.clone(), number: idx + 1, text: String::from(text) }
+        ).collect::<Vec<_>>();
+        let line_count = lines.len();
+        Lines { path, lines: lines.into_iter(), line_count, end: "end of input" }
+    }
+
+    fn section(path: Rc<Path>, lines: Vec<Line>) -> Self {
+        let line_count = lines.last().map_or(0, |line| line.number);
+        Lines { path, lines: lines.into_iter(), line_count, end: "end of section" }
+    }
+
+    /// Splits the remaining lines into sections separated by empty lines, skipping empty sections.
+    /// Line numbers in errors still refer to the whole input.
+    pub fn sections(self) -> Sections {
+        let path = self.path.clone();
+        let line_count = self.line_count;
+        let mut sections = Vec::<Lines>::new();
+        let mut current = Vec::<Line>::new();
+        for line in self {
+            if line.text.trim().is_empty() {
+                if !current.is_empty() { sections.push(Self::section(path.clone(), std::mem::take(&mut current))) }
+            }
+            else {
+                current.push(line);
+            }
+        }
+        if !current.is_empty() { sections.push(Self::section(path.clone(), current)) }
+        Sections { path, sections: sections.into_iter(), line_count }
+    }
+
+    /// Returns the next line, or an error pointing past the last line if the input has ended.
+    pub fn expect_line(&mut self, expected: &str) -> Result<Line, InputError> {
+        self.next().ok_or_else(|| InputError {
+            line: Some(self.line_count + 1), found: Some(String::from(self.end)),
+            ..InputError::new(&self.path, expected)
+        })
+    }
+
+    /// Errors if any lines are left, pointing at the first of them.
+    pub fn expect_end(&mut self, expected: &str) -> Result<(), InputError> {
+        match self.next() {
+            None => Ok(()),
+            Some(line) => Err(line.error_at(1, expected, Some(format!("'{}'", line.text))))
+        }
+    }
+
+    /// Parses every remaining line with `f`, which maps a character to a cell or returns `None` for
+    /// characters that are not `expected`. All rows must be as long as the first one.
+    pub fn grid_with<A>(self, f: impl Fn(char) -> Option<A>, expected: &str) -> Result<Vec<Vec<A>>, InputError> {
+        let mut rows = Vec::<Vec<A>>::new();
+        for line in self {
+            let row = line.chars_with(&f, expected)?;
+            if let Some(first) = rows.first() {
+                if row.len() != first.len() {
+                    return Err(line.error_at(1, format!("a row of {} cells", first.len()), Some(format!("{} cells", row.len()))))
+                }
+            }
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// Parses the remaining lines as a rectangular grid of decimal digits.
+    pub fn digit_grid(self) -> Result<Vec<Vec<u32>>, InputError> {
+        self.grid_with(|c| c.to_digit(10), "a digit")
+    }
+
+    /// Parses the remaining lines as a rectangular grid of characters.
+    pub fn char_grid(self) -> Result<Vec<Vec<char>>, InputError> {
+        self.grid_with(Some, "a character")
+    }
+}
+impl Iterator for Lines {
+    type Item = Line;
+
+    fn next(&mut self) -> Option<Self::Item> { self.lines.next() }
+}
+
+/// Blocks of lines separated by empty lines, see [Lines::sections].
+pub struct Sections {
+    path: Rc<Path>,
+    sections: std::vec::IntoIter<Lines>,
+    line_count: usize
+}
+impl Sections {
+    /// Returns the next section, or an error pointing past the last line if the input has ended.
+    pub fn expect_section(&mut self, expected: &str) -> Result<Lines, InputError> {
+        self.next().ok_or_else(|| InputError {
+            line: Some(self.line_count + 1), found: Some(String::from("end of input")),
+            ..InputError::new(&self.path, expected)
+        })
+    }
+}
+impl Iterator for Sections {
+    type Item = Lines;
+
+    fn next(&mut self) -> Option<Self::Item> { self.sections.next() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str) -> Line {
+        Line { path: Rc::from(Path::new("test.txt")), number: 3, text: String::from(text) }
+    }
+
+    #[test]
+    fn column_of_slices() {
+        let line = line("12,34,56");
+        let text = line.as_str();
+        assert_eq!(line.column_of(text), Some(1));
+        assert_eq!(line.column_of(&text[3..5]), Some(4));
+        assert_eq!(line.column_of(&text[8..]), Some(9));
+    }
+
+    #[test]
+    fn column_of_other_tokens() {
+        let line = line("12,34,56");
+        let other = String::from("12,34,56 and more");
+        assert_eq!(line.column_of(&other), None);
+        assert_eq!(line.column_of(&other[3..5]), None);
+        assert_eq!(line.column_of("34"), None);
+
+        let err = line.error("34", "a number");
+        assert_eq!((err.line, err.column), (Some(3), None));
+        assert_eq!(err.to_string(), "test.txt:3: expected a number, found '34'");
+    }
+
+    fn lines(text: &str) -> Lines { Lines::new(Path::new("test.txt"), text) }
+
+    /// Where the error points and what it says.
+    fn located(err: InputError) -> (Option<usize>, Option<usize>, String) { (err.line, err.column, err.to_string()) }
+
+    #[test]
+    fn extract() {
+        let line = line("on x=10..12,y=-3..4");
+        let [state, x, y] = line.extract("{} x={},y={}").unwrap();
+        assert_eq!((state, x, y), ("on", "10..12", "-3..4"));
+        let [from, to] = line.extract_in(y, "{}..{}").unwrap();
+        assert_eq!(line.parse_tokens::<i32, 2>([from, to], "a number").unwrap(), [-3, 4]);
+
+        assert_eq!(
+            located(line.extract::<3>("{} x={};y={}").unwrap_err()),
+            (Some(3), Some(6), String::from("test.txt:3:6: expected '{} x={};y={}', found '10..12,y=-3..4'"))
+        );
+        assert_eq!(located(line.extract::<1>("on {}!").unwrap_err()).1, Some(4));
+        assert_eq!(
+            located(line.extract::<1>("on x=10..12,y=-3..4{}!").unwrap_err()),
+            (Some(3), Some(20), String::from("test.txt:3:20: expected 'on x=10..12,y=-3..4{}!', found end of line"))
+        );
+        assert_eq!(located(line.extract::<1>("off {}").unwrap_err()).1, Some(1));
+    }
+
+    #[test]
+    fn parse_tokens_points_at_the_bad_one() {
+        let line = line("6,x");
+        let [a, b] = line.extract("{},{}").unwrap();
+        assert_eq!(
+            located(line.parse_tokens::<u32, 2>([a, b], "a coordinate").unwrap_err()),
+            (Some(3), Some(3), String::from("test.txt:3:3: expected a coordinate, found 'x'"))
+        );
+    }
+
+    #[test]
+    fn list() {
+        let line = line("  3   14 15 ");
+        assert_eq!(line.list::<u32>(line.as_str(), " ", "a number").unwrap(), [3, 14, 15]);
+        let line = self::line("1,2,,4");
+        assert_eq!(
+            located(line.list::<u32>(line.as_str(), ",", "a number").unwrap_err()),
+            (Some(3), Some(5), String::from("test.txt:3:5: expected a number, found ''"))
+        );
+    }
+
+    #[test]
+    fn char_array() {
+        let line = line("AB -> C");
+        let [pair, insert] = line.extract("{} -> {}").unwrap();
+        assert_eq!(line.char_array::<2>(pair, "a pair").unwrap(), ['A', 'B']);
+        assert_eq!(located(line.char_array::<2>(insert, "a pair").unwrap_err()).1, Some(7));
+    }
+
+    #[test]
+    fn sections() {
+        let mut sections = lines("1,2\n\n\na\nb\n\nc").sections();
+        assert_eq!(sections.expect_section("numbers").unwrap().map(|line| line.number).collect::<Vec<_>>(), [1]);
+        let mut letters = sections.expect_section("letters").unwrap();
+        assert_eq!(letters.expect_line("a").unwrap().text, "a");
+        assert_eq!(
+            located(letters.expect_end("one letter").unwrap_err()),
+            (Some(5), Some(1), String::from("test.txt:5:1: expected one letter, found 'b'"))
+        );
+        let mut last = sections.expect_section("the last letter").unwrap();
+        last.next();
+        assert_eq!(
+            located(last.expect_line("another letter").unwrap_err()),
+            (Some(8), None, String::from("test.txt:8: expected another letter, found end of section"))
+        );
+        assert_eq!(
+            located(sections.expect_section("more").err().unwrap()),
+            (Some(8), None, String::from("test.txt:8: expected more, found end of input"))
+        );
+    }
+
+    #[test]
+    fn grid_with() {
+        assert_eq!(lines("12\n34").digit_grid().unwrap(), [[1, 2], [3, 4]]);
+        assert_eq!(lines("#.\n.#").grid_with(|c| Some(c == '#'), "# or .").unwrap(), [[true, false], [false, true]]);
+        assert_eq!(
+            located(lines("12\n3x").digit_grid().unwrap_err()),
+            (Some(2), Some(2), String::from("test.txt:2:2: expected a digit, found 'x'"))
+        );
+        assert_eq!(
+            located(lines("12\n345").digit_grid().unwrap_err()),
+            (Some(2), Some(1), String::from("test.txt:2:1: expected a row of 2 cells, found 3 cells"))
+        );
+    }
+}
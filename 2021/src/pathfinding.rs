@@ -0,0 +1,223 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use crate::flood_fill::Connectivity;
+use crate::geometry::Vec2;
+use crate::grid::GridView;
+
+/// Result of a shortest path search.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ShortestPath {
+    /// Sum of the costs of every cell on the path except the start.
+    pub cost: u64,
+    /// Coordinates from the start to the goal, both included.
+    pub path: Vec<Vec2>
+}
+
+/// Cheapest path from `start` to `goal` where `cost` gives the price of entering a cell, `None` for
+/// cells that can not be entered.
+pub fn dijkstra<V : GridView>(
+    grid: &V, start: Vec2, goal: Vec2, connectivity: Connectivity, cost: impl Fn(&V::Cell) -> Option<u64>
+) -> Option<ShortestPath> {
+    search(grid, start, goal, connectivity, cost, |_| 0)
+}
+
+/// [dijkstra] guided by the Manhattan distance to the goal, or the Chebyshev distance with
+/// diagonals. The guidance is only admissible when entering any cell costs at least 1.
+pub fn a_star<V : GridView>(
+    grid: &V, start: Vec2, goal: Vec2, connectivity: Connectivity, cost: impl Fn(&V::Cell) -> Option<u64>
+) -> Option<ShortestPath> {
+    search(grid, start, goal, connectivity, cost, |c| {
+        (if connectivity.diagonals() { c.chebyshev(&goal) } else { c.manhattan(&goal) }) as u64
+    })
+}
+
+/// [dijkstra] for grids where entering a cell costs either 0 or 1, using a deque instead of a heap.
+///
+/// Panics if `cost` returns anything else.
+pub fn zero_one_bfs<V : GridView>(
+    grid: &V, start: Vec2, goal: Vec2, connectivity: Connectivity, cost: impl Fn(&V::Cell) -> Option<u64>
+) -> Option<ShortestPath> {
+    let mut state = State::new(grid, start, goal)?;
+    let mut queue = VecDeque::from([start]);
+    while let Some(c) = queue.pop_front() {
+        if c == goal { break }
+        if !state.visit(&c) { continue }
+
+        let dist = state.best(&c);
+        for next in grid.neighbors(&c, connectivity.diagonals()) {
+            let Some(step) = grid.cell(&next).and_then(|cell| cost(&cell)) else { continue };
+            assert!(step <= 1, "0-1 BFS step from {} to {} costs {}", c, next, step);
+            if state.relax(&c, next, dist + step) {
+                if step == 0 { queue.push_front(next) } else { queue.push_back(next) }
+            }
+        }
+    }
+    state.into_path(goal)
+}
+
+fn search<V : GridView>(
+    grid: &V, start: Vec2, goal: Vec2, connectivity: Connectivity,
+    cost: impl Fn(&V::Cell) -> Option<u64>, heuristic: impl Fn(&Vec2) -> u64
+) -> Option<ShortestPath> {
+    let mut state = State::new(grid, start, goal)?;
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0, start.as_tuple()))]);
+    while let Some(Reverse((_, dist, (x, y)))) = heap.pop() {
+        let c = Vec2::new(x, y);
+        if c == goal { break }
+        if !state.visit(&c) || dist > state.best(&c) { continue }
+
+        for next in grid.neighbors(&c, connectivity.diagonals()) {
+            let Some(step) = grid.cell(&next).and_then(|cell| cost(&cell)) else { continue };
+            let next_dist = dist + step;
+            if state.relax(&c, next, next_dist) {
+                heap.push(Reverse((next_dist + heuristic(&next), next_dist, next.as_tuple())));
+            }
+        }
+    }
+    state.into_path(goal)
+}
+
+/// Best known costs and predecessors, stored densely in row-major order.
+struct State {
+    cols: usize,
+    best: Vec<u64>,
+    came_from: Vec<Option<Vec2>>,
+    visited: Vec<bool>
+}
+impl State {
+    /// `None` if either end is outside the grid.
+    fn new<V : GridView>(grid: &V, start: Vec2, goal: Vec2) -> Option<Self> {
+        let (rows, cols) = grid.bounds();
+        if [start, goal].iter().any(|c| c.x >= rows || c.y >= cols) { return None }
+
+        let mut state = State {
+            cols, best: vec![u64::MAX; rows * cols], came_from: vec![None; rows * cols],
+            visited: vec![false; rows * cols]
+        };
+        let idx = state.index(&start);
+        state.best[idx] = 0;
+        Some(state)
+    }
+
+    fn index(&self, c: &Vec2) -> usize { c.x * self.cols + c.y }
+
+    fn best(&self, c: &Vec2) -> u64 { self.best[self.index(c)] }
+
+    /// Marks `c` as settled, `false` if it already was.
+    fn visit(&mut self, c: &Vec2) -> bool {
+        let idx = self.index(c);
+        !std::mem::replace(&mut self.visited[idx], true)
+    }
+
+    /// Records reaching `to` from `from` at `dist` if that is cheaper than what is known.
+    fn relax(&mut self, from: &Vec2, to: Vec2, dist: u64) -> bool {
+        let idx = self.index(&to);
+        if self.visited[idx] || dist >= self.best[idx] { return false }
+        self.best[idx] = dist;
+        self.came_from[idx] = Some(*from);
+        true
+    }
+
+    fn into_path(self, goal: Vec2) -> Option<ShortestPath> {
+        let cost = self.best(&goal);
+        if cost == u64::MAX { return None }
+
+        let mut path = vec![goal];
+        while let Some(previous) = self.came_from[self.index(path.last().unwrap())] {
+            path.push(previous);
+        }
+        path.reverse();
+        Some(ShortestPath { cost, path })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::{GridMap, Tiled};
+    use super::*;
+
+    /// The day 15 example, where entering a cell costs its risk level.
+    fn risks() -> GridMap<u32> {
+        let rows = [
+            "1163751742", "1381373672", "2136511328", "3694931569", "7463417111",
+            "1319128137", "1359912421", "3125421639", "1293138521", "2311944581"
+        ];
+        GridMap::new_with_data(rows.iter().map(|row| row.chars().map(|c| c.to_digit(10).unwrap()).collect()).collect())
+    }
+
+    fn risk(cell: &u32) -> Option<u64> { Some(*cell as u64) }
+
+    /// Checks that the path is made of neighbouring cells and costs what it claims.
+    fn assert_valid<V : GridView<Cell = u32>>(grid: &V, found: &ShortestPath, start: Vec2, goal: Vec2) {
+        assert_eq!((found.path[0], *found.path.last().unwrap()), (start, goal));
+        assert!(found.path.windows(2).all(|step| step[0].manhattan(&step[1]) == 1), "{:?}", found.path);
+        let cost = found.path[1..].iter().map(|c| grid.cell(c).unwrap() as u64).sum::<u64>();
+        assert_eq!(cost, found.cost);
+    }
+
+    #[test]
+    fn day15_example() {
+        let grid = risks();
+        let (start, goal) = (Vec2::new(0, 0), Vec2::new(9, 9));
+        for search in [dijkstra, a_star] {
+            let found = search(&grid, start, goal, Connectivity::Four, risk).unwrap();
+            assert_eq!(found.cost, 40);
+            assert_valid(&grid, &found, start, goal);
+        }
+
+        let tiled = Tiled::new(&grid, 5, 5, |risk, (row, col)| (risk + row as u32 + col as u32 - 1) % 9 + 1);
+        let found = dijkstra(&tiled, start, Vec2::new(49, 49), Connectivity::Four, risk).unwrap();
+        assert_eq!(found.cost, 315);
+        assert_valid(&tiled, &found, start, Vec2::new(49, 49));
+    }
+
+    #[test]
+    fn a_star_agrees_with_dijkstra() {
+        let grid = risks();
+        for connectivity in [Connectivity::Four, Connectivity::Eight] {
+            for goal in grid.each_coord() {
+                let start = Vec2::new(3, 4);
+                assert_eq!(
+                    a_star(&grid, start, goal, connectivity, risk).map(|found| found.cost),
+                    dijkstra(&grid, start, goal, connectivity, risk).map(|found| found.cost),
+                    "{:?} to {}", connectivity, goal
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn unreachable_and_trivial_goals() {
+        // The 9s are walls cutting off the right column.
+        let grid = GridMap::new_with_data(vec![vec![1, 9, 1], vec![1, 9, 1], vec![1, 9, 1]]);
+        let walls = |cell: &u32| if *cell == 9 { None } else { Some(*cell as u64) };
+        let (start, goal) = (Vec2::new(0, 0), Vec2::new(2, 2));
+        assert_eq!(dijkstra(&grid, start, goal, Connectivity::Eight, walls), None);
+        assert_eq!(a_star(&grid, start, goal, Connectivity::Four, walls), None);
+        assert_eq!(zero_one_bfs(&grid, start, goal, Connectivity::Four, |cell| walls(cell).map(|_| 1)), None);
+        assert_eq!(dijkstra(&grid, start, Vec2::new(3, 0), Connectivity::Four, walls), None);
+
+        let at_start = ShortestPath { cost: 0, path: vec![start] };
+        assert_eq!(dijkstra(&grid, start, start, Connectivity::Four, walls), Some(at_start.clone()));
+        assert_eq!(a_star(&grid, start, start, Connectivity::Four, walls), Some(at_start.clone()));
+        assert_eq!(zero_one_bfs(&grid, start, start, Connectivity::Four, |_| Some(1)), Some(at_start));
+    }
+
+    #[test]
+    fn zero_one_bfs_agrees_with_dijkstra() {
+        let grid = risks();
+        let zero_one = |cell: &u32| Some((*cell % 2) as u64);
+        let (start, goal) = (Vec2::new(0, 0), Vec2::new(9, 9));
+        let found = zero_one_bfs(&grid, start, goal, Connectivity::Four, zero_one).unwrap();
+        let expected = dijkstra(&grid, start, goal, Connectivity::Four, zero_one).unwrap();
+        assert_eq!(found.cost, expected.cost);
+        assert_eq!(found.path[0], start);
+        assert_eq!(found.path[1..].iter().map(|c| zero_one(grid.get(c).unwrap()).unwrap()).sum::<u64>(), found.cost);
+    }
+
+    #[test]
+    #[should_panic(expected = "0-1 BFS step from (0,1) to (0,2) costs 6")]
+    fn zero_one_bfs_rejects_other_weights() {
+        zero_one_bfs(&risks(), Vec2::new(0, 0), Vec2::new(9, 9), Connectivity::Four, |cell| Some(*cell as u64));
+    }
+}
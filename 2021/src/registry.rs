@@ -0,0 +1,49 @@
+use crate::{day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13, day14, day15, day16, day17};
+use advent_of_code::solution::Runner;
+
+pub const PARTS: [u8; 2] = [1, 2];
+
+/// A single puzzle day and its solution.
+pub struct Day {
+    pub day: u8,
+    pub solution: &'static dyn Runner
+}
+impl Day {
+    pub fn has_part(&self, part: u8) -> bool {
+        PARTS.contains(&part)
+    }
+
+    pub fn part_numbers(&self) -> impl Iterator<Item = u8> {
+        PARTS.into_iter()
+    }
+
+    /// Span to enter while running a part, so its diagnostics can be filtered per day, for example
+    /// with `AOC_LOG='[day{day=16}]=trace'`.
+    pub fn span(&self, part: u8) -> tracing::Span {
+        tracing::info_span!("day", day = self.day, part)
+    }
+}
+
+pub static DAYS: &[Day] = &[
+    Day { day: 1, solution: &day1::Day1 },
+    Day { day: 2, solution: &day2::Day2 },
+    Day { day: 3, solution: &day3::Day3 },
+    Day { day: 4, solution: &day4::Day4 },
+    Day { day: 5, solution: &day5::Day5 { counting: day5::Counting::Sweep } },
+    Day { day: 6, solution: &day6::Day6 },
+    Day { day: 7, solution: &day7::Day7 },
+    Day { day: 8, solution: &day8::Day8 },
+    Day { day: 9, solution: &day9::Day9 },
+    Day { day: 10, solution: &day10::Day10 },
+    Day { day: 11, solution: &day11::Day11 },
+    Day { day: 12, solution: &day12::Day12 },
+    Day { day: 13, solution: &day13::Day13 },
+    Day { day: 14, solution: &day14::Day14 },
+    Day { day: 15, solution: &day15::Day15 },
+    Day { day: 16, solution: &day16::Day16 },
+    Day { day: 17, solution: &day17::Day17 },
+];
+
+pub fn find(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io;
+use std::io::{BufWriter, IsTerminal, Write};
+use std::path::Path;
+use crate::geometry::Vec2;
+use crate::grid::GridView;
+
+/// A colour for 24 bit images.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    /// `level` out of `max` from black to white, for example the height of a cell.
+    pub fn gray(level: u32, max: u32) -> Rgb {
+        let v = (level.min(max) * 255 / max.max(1)) as u8;
+        Rgb(v, v, v)
+    }
+}
+
+/// Highlight colours, which map onto the basic ANSI terminal colours.
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone)]
+pub enum Color { Red, Green, Yellow, Blue, Magenta, Cyan }
+impl Color {
+    pub const ALL: [Color; 6] = [Color::Red, Color::Green, Color::Yellow, Color::Blue, Color::Magenta, Color::Cyan];
+
+    /// Bold foreground colour escape code.
+    fn ansi(&self) -> &'static str {
+        match self {
+            Color::Red => "\x1b[1;31m",
+            Color::Green => "\x1b[1;32m",
+            Color::Yellow => "\x1b[1;33m",
+            Color::Blue => "\x1b[1;34m",
+            Color::Magenta => "\x1b[1;35m",
+            Color::Cyan => "\x1b[1;36m"
+        }
+    }
+
+    pub fn rgb(&self) -> Rgb {
+        match self {
+            Color::Red => Rgb(220, 50, 47),
+            Color::Green => Rgb(133, 153, 0),
+            Color::Yellow => Rgb(181, 137, 0),
+            Color::Blue => Rgb(38, 139, 210),
+            Color::Magenta => Rgb(211, 54, 130),
+            Color::Cyan => Rgb(42, 161, 152)
+        }
+    }
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Renders a [GridView] as text or an image with sets of coordinates highlighted, for example a
+/// basin or a shortest path. When highlights overlap the one added last wins.
+pub struct Render<'a, G : GridView> {
+    grid: &'a G,
+    highlights: Vec<(HashSet<Vec2>, Color)>
+}
+impl<'a, G : GridView> Render<'a, G> {
+    pub fn new(grid: &'a G) -> Self { Render { grid, highlights: Vec::new() } }
+
+    pub fn highlight(mut self, coords: impl IntoIterator<Item = Vec2>, color: Color) -> Self {
+        self.highlights.push((coords.into_iter().collect(), color));
+        self
+    }
+
+    fn highlight_of(&self, c: &Vec2) -> Option<Color> {
+        self.highlights.iter().rev().find(|(coords, _)| coords.contains(c)).map(|(_, color)| *color)
+    }
+
+    /// Every coordinate within the bounds row by row like [Grid::render](crate::grid::Grid::render),
+    /// with highlighted cells in ANSI colours.
+    pub fn ansi(&self, cell: impl Fn(Option<&G::Cell>) -> String) -> String {
+        self.render_text(cell, true)
+    }
+
+    /// [Render::ansi] when stderr, where diagnostics go, is a terminal, without the highlights
+    /// otherwise.
+    pub fn text(&self, cell: impl Fn(Option<&G::Cell>) -> String) -> String {
+        self.render_text(cell, io::stderr().is_terminal())
+    }
+
+    fn render_text(&self, cell: impl Fn(Option<&G::Cell>) -> String, colored: bool) -> String {
+        let (rows, cols) = self.grid.bounds();
+        let mut out = String::new();
+        for x in 0..rows {
+            for y in 0..cols {
+                let c = Vec2::new(x, y);
+                let text = cell(self.grid.cell(&c).as_ref());
+                match self.highlight_of(&c).filter(|_| colored) {
+                    Some(color) => {
+                        out.push_str(color.ansi());
+                        out.push_str(&text);
+                        out.push_str(ANSI_RESET);
+                    }
+                    None => out.push_str(&text)
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Colour of every pixel, row by row, each cell being a `scale` × `scale` square.
+    fn pixels(&self, color: impl Fn(Option<&G::Cell>) -> Rgb, scale: usize) -> (usize, usize, Vec<u8>) {
+        let (rows, cols) = self.grid.bounds();
+        let (height, width) = (rows * scale, cols * scale);
+        let mut pixels = Vec::with_capacity(height * width * 3);
+        for x in 0..height {
+            for y in 0..width {
+                let c = Vec2::new(x / scale, y / scale);
+                let Rgb(r, g, b) =
+                    self.highlight_of(&c).map_or_else(|| color(self.grid.cell(&c).as_ref()), |color| color.rgb());
+                pixels.extend([r, g, b]);
+            }
+        }
+        (height, width, pixels)
+    }
+
+    /// Writes a binary PPM (P6) image.
+    pub fn write_ppm(
+        &self, out: &mut impl Write, color: impl Fn(Option<&G::Cell>) -> Rgb, scale: usize
+    ) -> io::Result<()> {
+        let (height, width, pixels) = self.pixels(color, scale);
+        write!(out, "P6\n{} {}\n255\n", width, height)?;
+        out.write_all(&pixels)
+    }
+
+    /// Writes an RGB PNG image.
+    pub fn write_png(
+        &self, out: &mut impl Write, color: impl Fn(Option<&G::Cell>) -> Rgb, scale: usize
+    ) -> io::Result<()> {
+        let (height, width, pixels) = self.pixels(color, scale);
+        let mut encoder = png::Encoder::new(out, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&pixels)?;
+        Ok(writer.finish()?)
+    }
+
+    /// Writes a PNG or PPM image depending on the extension of `path`.
+    pub fn save(
+        &self, path: impl AsRef<Path>, color: impl Fn(Option<&G::Cell>) -> Rgb, scale: usize
+    ) -> io::Result<()> {
+        let path = path.as_ref();
+        let extension = path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase());
+        let write_png = match extension.as_deref() {
+            Some("png") => true,
+            Some("ppm") => false,
+            _ => return Err(io::Error::new(
+                io::ErrorKind::InvalidInput, format!("{}: expected a .png or .ppm file", path.display())
+            ))
+        };
+        let mut out = BufWriter::new(File::create(path)?);
+        if write_png { self.write_png(&mut out, color, scale)? } else { self.write_ppm(&mut out, color, scale)? }
+        out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::GridMap;
+    use super::*;
+
+    #[test]
+    fn ppm_with_highlight() {
+        let grid = GridMap::new_with_data(vec![vec![0, 9], vec![3, 6]]);
+        let mut out = Vec::new();
+        Render::new(&grid).highlight([Vec2::new(0, 0)], Color::Red)
+            .write_ppm(&mut out, |cell| Rgb::gray(*cell.unwrap(), 9), 1)
+            .unwrap();
+
+        let header = b"P6\n2 2\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        assert_eq!(&out[header.len()..], [220, 50, 47, 255, 255, 255, 85, 85, 85, 170, 170, 170]);
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use itertools::Itertools;
+use crate::geometry::Vec2Signed;
+use crate::math::gcd;
+
+/// A segment between integer endpoints, covering the integer points lying exactly on it like
+/// [LatticePoints](crate::geometry::LatticePoints).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Segment {
+    pub from: Vec2Signed,
+    pub to: Vec2Signed
+}
+
+/// The integer points of a line of one direction: `b * x - a * y = offset` for the direction `(a, b)`,
+/// where `a` and `b` have no common divisor and `a > 0`, or `a == 0` and `b == 1`. Points on it are
+/// numbered consecutively along the direction.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+struct LatticeLine {
+    a: i64,
+    b: i64,
+    offset: i128
+}
+impl LatticeLine {
+    /// The line through the segment and the numbers of its endpoints, in ascending order.
+    fn of(segment: &Segment) -> (LatticeLine, i64, i64) {
+        let d = segment.to - segment.from;
+        let divisor = gcd(d.x.unsigned_abs(), d.y.unsigned_abs()) as i64;
+        let (mut a, mut b) = if divisor == 0 { (0, 1) } else { (d.x / divisor, d.y / divisor) };
+        if a < 0 || (a == 0 && b < 0) { (a, b) = (-a, -b); }
+
+        let p = segment.from;
+        let line = LatticeLine { a, b, offset: b as i128 * p.x as i128 - a as i128 * p.y as i128 };
+        let (from, to) = (line.number(&segment.from), line.number(&segment.to));
+        (line, from.min(to), from.max(to))
+    }
+
+    /// Position of a point of the line along it.
+    fn number(&self, p: &Vec2Signed) -> i64 {
+        if self.a != 0 { p.x.div_euclid(self.a) } else { p.y }
+    }
+
+    /// The integer point where both lines cross, `None` if they are parallel or cross between
+    /// integer points.
+    fn crossing(&self, other: &LatticeLine) -> Option<Vec2Signed> {
+        let (a1, b1, a2, b2) = (self.a as i128, self.b as i128, other.a as i128, other.b as i128);
+        let det = a1 * b2 - a2 * b1;
+        if det == 0 { return None }
+
+        let x = a1 * other.offset - a2 * self.offset;
+        let y = b1 * other.offset - b2 * self.offset;
+        if x % det != 0 || y % det != 0 { return None }
+        Some(Vec2Signed::new((x / det) as i64, (y / det) as i64))
+    }
+}
+
+/// How many segments cover the points of one line: runs of consecutive point numbers, `from` and
+/// `to` included, with the same non-zero coverage, in ascending order.
+#[derive(Debug)]
+struct Coverage {
+    line: LatticeLine,
+    runs: Vec<(i64, i64, usize)>
+}
+impl Coverage {
+    /// Sweeps over the start and end events of the segments of the line.
+    fn sweep(line: LatticeLine, ranges: &[(i64, i64)]) -> Self {
+        let events = ranges.iter()
+            .flat_map(|(from, to)| [(*from, 1), (to + 1, -1)])
+            .sorted()
+            .collect_vec();
+
+        let mut runs = Vec::new();
+        let mut depth = 0i64;
+        for (idx, (at, change)) in events.iter().enumerate() {
+            depth += change;
+            match events.get(idx + 1) {
+                Some((next, _)) if next > at && depth > 0 => runs.push((*at, next - 1, depth as usize)),
+                _ => {}
+            }
+        }
+        Coverage { line, runs }
+    }
+
+    fn at(&self, p: &Vec2Signed) -> usize {
+        let number = self.line.number(p);
+        let idx = self.runs.partition_point(|(_, to, _)| *to < number);
+        match self.runs.get(idx) {
+            Some((from, _, count)) if *from <= number => *count,
+            _ => 0
+        }
+    }
+
+    /// Points covered at least `at_least` times.
+    fn count(&self, at_least: usize) -> u64 {
+        self.runs.iter()
+            .filter(|(_, _, count)| *count >= at_least)
+            .map(|(from, to, _)| (to - from + 1) as u64)
+            .sum()
+    }
+}
+
+/// Number of integer points covered by at least `at_least` segments, without visiting every covered
+/// point, so it stays cheap for huge coordinates.
+///
+/// The segments on each line are swept to find how often each stretch of the line is covered.
+/// Points where lines cross are then corrected for, which is quadratic in the number of distinct
+/// lines.
+pub fn count_covered(segments: &[Segment], at_least: usize) -> u64 {
+    let lines = segments.iter()
+        .map(|segment| {
+            let (line, from, to) = LatticeLine::of(segment);
+            (line, (from, to))
+        })
+        .into_group_map()
+        .into_iter()
+        .map(|(line, ranges)| Coverage::sweep(line, &ranges))
+        .collect_vec();
+
+    let mut count = lines.iter().map(|coverage| coverage.count(at_least)).sum::<u64>();
+
+    // Lines through every crossing point that is covered on at least two of them.
+    let mut crossings = HashMap::<Vec2Signed, Vec<usize>>::new();
+    for (i, j) in (0..lines.len()).tuple_combinations() {
+        let Some(p) = lines[i].line.crossing(&lines[j].line) else { continue };
+        if lines[i].at(&p) > 0 && lines[j].at(&p) > 0 {
+            crossings.entry(p).or_default().extend([i, j]);
+        }
+    }
+    for (p, mut through) in crossings {
+        through.sort_unstable();
+        through.dedup();
+        let coverages = through.iter().map(|idx| lines[*idx].at(&p)).collect_vec();
+        // Each line counted the point on its own already.
+        count -= coverages.iter().filter(|coverage| **coverage >= at_least).count() as u64;
+        if coverages.iter().sum::<usize>() >= at_least { count += 1; }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::LatticePoints;
+    use super::*;
+
+    fn segment(x1: i64, y1: i64, x2: i64, y2: i64) -> Segment {
+        Segment { from: Vec2Signed::new(x1, y1), to: Vec2Signed::new(x2, y2) }
+    }
+
+    /// Counts by visiting every point of every segment.
+    fn tally(segments: &[Segment], at_least: usize) -> u64 {
+        let counts = segments.iter().flat_map(|s| LatticePoints::new(s.from, s.to)).counts();
+        counts.values().filter(|count| **count >= at_least).count() as u64
+    }
+
+    fn assert_counts(segments: &[Segment]) {
+        for at_least in 1..=3 {
+            assert_eq!(count_covered(segments, at_least), tally(segments, at_least), "at least {} of {:?}", at_least, segments);
+        }
+    }
+
+    #[test]
+    fn collinear_overlaps() {
+        assert_counts(&[segment(0, 0, 6, 6), segment(2, 2, 9, 9), segment(4, 4, 5, 5)]);
+        assert_counts(&[segment(0, 0, 0, 9), segment(0, 3, 0, 5), segment(0, 5, 0, 12)]);
+        // Parallel, but on different lines.
+        assert_counts(&[segment(0, 0, 4, 6), segment(1, 0, 5, 6), segment(2, 3, 6, 9)]);
+    }
+
+    #[test]
+    fn opposite_directions() {
+        assert_counts(&[segment(0, 0, 6, 9), segment(8, 12, 2, 3)]);
+        assert_counts(&[segment(5, 0, 0, 0), segment(1, 0, 7, 0), segment(3, 0, 3, 0)]);
+    }
+
+    #[test]
+    fn single_points() {
+        assert_counts(&[segment(3, 3, 3, 3)]);
+        assert_counts(&[segment(3, 3, 3, 3), segment(3, 3, 3, 3), segment(0, 0, 6, 6)]);
+        assert_counts(&[segment(1, 2, 1, 2), segment(0, 2, 4, 2), segment(1, 0, 1, 5)]);
+    }
+
+    #[test]
+    fn crossing_between_lattice_points() {
+        // The diagonals cross at (0.5, 0.5) and (1.5, 1.5), the third line crosses both at integer points.
+        assert_counts(&[segment(0, 0, 1, 1), segment(0, 1, 1, 0)]);
+        assert_counts(&[segment(0, 0, 4, 4), segment(0, 3, 3, 0), segment(0, 2, 6, 2)]);
+        // Slopes 1/2 and 2 cross at (2/3, 4/3).
+        assert_counts(&[segment(0, 1, 4, 3), segment(0, 0, 2, 4)]);
+    }
+
+    #[test]
+    fn random_segments() {
+        // A fixed linear congruential generator keeps the cases reproducible.
+        let mut state = 42u64;
+        let mut next = |range: i64| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 33) as i64 % range - range / 2
+        };
+        for _ in 0..200 {
+            let count = next(8) + 5;
+            let segments = (0..count).map(|_| {
+                let from = Vec2Signed::new(next(12), next(12));
+                // Mostly axis aligned and diagonal lines, like the puzzle's, with some of any slope.
+                let (dx, dy) = match next(8) + 4 {
+                    0 => (next(12), 0),
+                    1 => (0, next(12)),
+                    2 | 3 => { let d = next(12); (d, if next(2) == 0 { d } else { -d }) }
+                    _ => (next(12), next(12))
+                };
+                Segment { from, to: from + Vec2Signed::new(dx, dy) }
+            }).collect_vec();
+            assert_counts(&segments);
+        }
+    }
+}
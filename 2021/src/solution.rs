@@ -0,0 +1,89 @@
+use core::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+use num_bigint::BigUint;
+use crate::parsing::{Input, InputError};
+
+/// The result of solving a puzzle part.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Answer {
+    Number(i128),
+    Text(String)
+}
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => f.write_str(s)
+        }
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self { Answer::Number(n as i128) }
+        })*
+    };
+}
+answer_from_number!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Numbers beyond `i128` become text.
+impl From<BigUint> for Answer {
+    fn from(n: BigUint) -> Self { i128::try_from(&n).map_or_else(|_| Answer::Text(n.to_string()), Answer::Number) }
+}
+impl From<String> for Answer {
+    fn from(s: String) -> Self { Answer::Text(s) }
+}
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self { Answer::Text(String::from(s)) }
+}
+
+/// A puzzle day: parses its input once and then solves both parts from the parsed form.
+///
+/// Parts must not print their answer, they return it. Diagnostics go to stderr.
+pub trait Solution {
+    type Parsed;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed, InputError>;
+    fn part1(&self, input: &Self::Parsed) -> Answer;
+    fn part2(&self, input: &Self::Parsed) -> Answer;
+}
+
+/// How long parsing and solving a part took.
+#[derive(Debug, Clone)]
+pub struct Timing {
+    pub answer: Answer,
+    pub parse: Duration,
+    pub solve: Duration
+}
+
+/// Object-safe view of a [Solution], used by the registry to hold days with different
+/// [Solution::Parsed] types.
+pub trait Runner: Sync {
+    /// Parses the input and solves the given part, returns `None` if there is no such part.
+    fn run(&self, part: u8, input: &Input) -> Option<Result<Answer, InputError>> {
+        self.time(part, input).map(|timing| timing.map(|t| t.answer))
+    }
+
+    /// Same as [Runner::run], but measures parsing and solving separately.
+    fn time(&self, part: u8, input: &Input) -> Option<Result<Timing, InputError>>;
+}
+impl<S : Solution + Sync> Runner for S {
+    fn time(&self, part: u8, input: &Input) -> Option<Result<Timing, InputError>> {
+        let solve: fn(&S, &S::Parsed) -> Answer = match part {
+            1 => S::part1,
+            2 => S::part2,
+            _ => return None
+        };
+
+        let started = Instant::now();
+        let parsed = match self.parse(input) {
+            Ok(parsed) => parsed,
+            Err(err) => return Some(Err(err))
+        };
+        let parsed_at = Instant::now();
+        let answer = solve(self, &parsed);
+        let solved_at = Instant::now();
+        Some(Ok(Timing { answer, parse: parsed_at - started, solve: solved_at - parsed_at }))
+    }
+}